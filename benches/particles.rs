@@ -0,0 +1,137 @@
+use std::{
+    collections::VecDeque,
+    hint::black_box,
+    time::{Duration, SystemTime},
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use firework_rs::{
+    fireworks::{Firework, FireworkConfig, FireworkManager},
+    particle::{LifeState, ParticleConfig},
+    utils::gen_points_circle_normal,
+};
+use glam::Vec2;
+
+/// One frame at 20 fps
+const FRAME: Duration = Duration::from_millis(50);
+
+fn configs(n: usize) -> Vec<ParticleConfig> {
+    gen_points_circle_normal(300., n)
+        .into_iter()
+        .map(|v| {
+            ParticleConfig::new(
+                Vec2::ZERO,
+                v,
+                20,
+                Duration::from_secs(3600),
+                (255, 255, 255),
+            )
+        })
+        .collect()
+}
+
+/// Copy of `Particle` as it was before `ParticleStore`, with its own trail
+struct LegacyParticle {
+    pos: Vec2,
+    vel: Vec2,
+    trail: VecDeque<Vec2>,
+    life_state: LifeState,
+    time_elapsed: Duration,
+    config: ParticleConfig,
+}
+
+/// Copy of `FireworkConfig::additional_force` as it was before it became optional
+type LegacyForce = Box<dyn Fn(&LegacyParticle) -> Vec2>;
+
+impl LegacyParticle {
+    /// Copy of `Particle::update` as it was before fixed simulation steps
+    fn update(
+        &mut self,
+        duration: Duration,
+        config: &FireworkConfig,
+        additional_force: &LegacyForce,
+    ) {
+        const TIME_STEP: f32 = 0.001;
+        self.time_elapsed += duration;
+        self.life_state = cal_life_state(self.config.life_time, self.time_elapsed);
+        let mut t = 0.;
+        while t < duration.as_secs_f32() {
+            self.vel += TIME_STEP
+                * (Vec2::Y * 10. * config.gravity_scale
+                    - self.vel.normalize() * self.vel.length().powi(2) * config.ar_scale
+                    + additional_force(self));
+            self.pos += TIME_STEP * self.vel;
+            t += TIME_STEP;
+        }
+        self.trail.pop_front();
+        self.trail.push_back(self.pos);
+    }
+}
+
+/// Copy of `cal_life_state` as it was before `LifeCurve`
+fn cal_life_state(life_time: Duration, current_elapsed: Duration) -> LifeState {
+    let p = current_elapsed.as_millis() as f32 / life_time.as_millis() as f32;
+    if p < 0.4 {
+        LifeState::Alive
+    } else if p < 0.65 {
+        LifeState::Declining
+    } else if p < 1. {
+        LifeState::Dying
+    } else {
+        LifeState::Dead
+    }
+}
+
+/// Copy of the particle loop of `Firework::update` as it was before `ParticleStore`
+fn legacy_frame(
+    particles: &mut Vec<LegacyParticle>,
+    duration: Duration,
+    config: &FireworkConfig,
+    additional_force: &LegacyForce,
+) {
+    particles
+        .iter_mut()
+        .for_each(|p| p.update(duration, config, additional_force));
+    particles.retain(|p| p.life_state != LifeState::Dead);
+}
+
+fn bench_frame(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame_update");
+    group.sample_size(10);
+    for n in [1_000, 10_000, 50_000] {
+        group.bench_with_input(BenchmarkId::new("legacy_vec_particle", n), &n, |b, &n| {
+            let config = FireworkConfig::default();
+            let additional_force: LegacyForce = Box::new(|_| Vec2::ZERO);
+            let mut particles = configs(n)
+                .into_iter()
+                .map(|c| LegacyParticle {
+                    pos: c.init_pos,
+                    vel: c.init_vel,
+                    trail: VecDeque::from(vec![c.init_pos; c.trail_length]),
+                    life_state: LifeState::Alive,
+                    time_elapsed: Duration::ZERO,
+                    config: c,
+                })
+                .collect::<Vec<_>>();
+            b.iter(|| legacy_frame(&mut particles, black_box(FRAME), &config, &additional_force));
+        });
+        group.bench_with_input(BenchmarkId::new("particle_store", n), &n, |b, &n| {
+            let mut fm = FireworkManager::default().with_firework(Firework {
+                init_time: SystemTime::UNIX_EPOCH,
+                particles: configs(n),
+                // Same zero additional force as the legacy path, so only the storage differs
+                config: FireworkConfig::default().with_additional_force(|_| Vec2::ZERO),
+                ..Default::default()
+            });
+            let mut now = SystemTime::UNIX_EPOCH;
+            b.iter(|| {
+                fm.update(now, black_box(FRAME));
+                now += FRAME;
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_frame);
+criterion_main!(benches);
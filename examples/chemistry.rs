@@ -0,0 +1,119 @@
+use std::{
+    io::{stdout, Result},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, KeyCode},
+    execute, terminal,
+};
+use firework_rs::{
+    chemistry::{Emission, MetalSalt},
+    config::Config,
+    fireworks::{Firework, FireworkConfig, FireworkManager},
+    particle::ParticleConfig,
+    term::Terminal,
+    utils::{explosion_gradient_2, gen_points_circle_normal},
+};
+use glam::Vec2;
+use rand::{thread_rng, Rng};
+
+fn main() -> Result<()> {
+    let mut stdout = stdout();
+    let (_width, _height) = terminal::size()?;
+    let mut is_running = true;
+    let cfg = Config::default();
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let mut time = SystemTime::now();
+    let mut term = Terminal::default();
+    let (w, h) = (_width as f32 / 2., _height as f32);
+    let mut fm = FireworkManager::default();
+    // One burst for each metal salt, from left to right
+    for (k, salt) in MetalSalt::ALL.into_iter().enumerate() {
+        fm.add_firework(gen_chemistry_firework(
+            Vec2::new(w * (k as f32 + 1.) / 8., h * 0.3),
+            Duration::from_secs_f32(k as f32 * 0.5),
+            Emission::Salt(salt),
+            0.,
+        ));
+    }
+    // Charcoal sparks cooling from orange to dull red
+    fm.add_firework(gen_chemistry_firework(
+        Vec2::new(w * 0.5, h * 0.55),
+        Duration::from_secs(4),
+        Emission::Blackbody { temperature: 2200. },
+        0.4,
+    ));
+    let mut fm = fm.enable_loop();
+
+    while is_running {
+        if event::poll(Duration::ZERO)? {
+            match event::read()? {
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
+                }
+                event::Event::Resize(_, _) => {
+                    fm.reset();
+                    term.reinit(&cfg);
+                }
+                _ => {}
+            };
+        }
+
+        let delta_time = SystemTime::now().duration_since(time).unwrap();
+        fm.update(time, delta_time);
+        time = SystemTime::now();
+
+        term.render(&fm, &cfg);
+        term.print(&mut stdout, &cfg);
+
+        if delta_time < Duration::from_secs_f32(0.05) {
+            let rem = Duration::from_secs_f32(0.05) - delta_time;
+            sleep(rem);
+        }
+    }
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    Ok(())
+}
+
+/// Generate a burst of `Particle`s with `emission`, `gravity_scale` above zero lets them droop like a willow
+fn gen_chemistry_firework(
+    center: Vec2,
+    spawn_after: Duration,
+    emission: Emission,
+    gravity_scale: f32,
+) -> Firework {
+    let particles = gen_points_circle_normal(180., 40)
+        .into_iter()
+        .map(|v| {
+            ParticleConfig::new(
+                center,
+                v,
+                thread_rng().gen_range(6..10),
+                Duration::from_secs_f32(thread_rng().gen_range(2.2..2.8)),
+                (255, 255, 255),
+            )
+            .with_emission(emission)
+        })
+        .collect();
+    let mut config = FireworkConfig::default()
+        .with_gravity_scale(gravity_scale)
+        .with_gradient_scale(explosion_gradient_2);
+    config.set_enable_gradient(true);
+    Firework {
+        init_time: SystemTime::now(),
+        spawn_after,
+        center,
+        particles,
+        config,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,118 @@
+use std::{
+    io::{stdout, Result},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, KeyCode},
+    execute, terminal,
+};
+use firework_rs::{
+    config::Config,
+    fireworks::{Firework, FireworkConfig, FireworkManager},
+    particle::{Effect, ParticleConfig},
+    term::Terminal,
+    utils::gen_points_circle_normal,
+};
+use glam::Vec2;
+use rand::{thread_rng, Rng};
+
+fn main() -> Result<()> {
+    let mut stdout = stdout();
+    let (_width, _height) = terminal::size()?;
+    let mut is_running = true;
+    let cfg = Config::default();
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let mut time = SystemTime::now();
+    let mut term = Terminal::default();
+    let (w, h) = (_width as f32 / 2., _height as f32);
+    let mut fm = FireworkManager::default()
+        .with_firework(gen_effect_firework(
+            Vec2::new(w * 0.2, h * 0.4),
+            Duration::ZERO,
+            Effect::Strobe { frequency: 6. },
+            (255, 255, 255),
+        ))
+        .with_firework(gen_effect_firework(
+            Vec2::new(w * 0.5, h * 0.35),
+            Duration::from_secs(1),
+            Effect::Glitter { density: 0.6 },
+            (255, 200, 90),
+        ))
+        .with_firework(gen_effect_firework(
+            Vec2::new(w * 0.8, h * 0.4),
+            Duration::from_secs(2),
+            Effect::Crackle {
+                sparks: 4,
+                speed: 12.,
+            },
+            (255, 120, 60),
+        ))
+        .enable_loop();
+
+    while is_running {
+        if event::poll(Duration::ZERO)? {
+            match event::read()? {
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
+                }
+                event::Event::Resize(_, _) => {
+                    fm.reset();
+                    term.reinit(&cfg);
+                }
+                _ => {}
+            };
+        }
+
+        let delta_time = SystemTime::now().duration_since(time).unwrap();
+        fm.update(time, delta_time);
+        time = SystemTime::now();
+
+        term.render(&fm, &cfg);
+        term.print(&mut stdout, &cfg);
+
+        if delta_time < Duration::from_secs_f32(0.05) {
+            let rem = Duration::from_secs_f32(0.05) - delta_time;
+            sleep(rem);
+        }
+    }
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    Ok(())
+}
+
+fn gen_effect_firework(
+    center: Vec2,
+    spawn_after: Duration,
+    effect: Effect,
+    color: (u8, u8, u8),
+) -> Firework {
+    let particles = gen_points_circle_normal(250., 40)
+        .iter()
+        .map(|v| {
+            ParticleConfig::new(
+                center,
+                *v,
+                thread_rng().gen_range(12..18),
+                Duration::from_secs_f32(thread_rng().gen_range(2.0..2.6)),
+                color,
+            )
+            .with_effect(effect)
+        })
+        .collect();
+    Firework {
+        init_time: SystemTime::now(),
+        spawn_after,
+        center,
+        particles,
+        config: FireworkConfig::default(),
+        ..Default::default()
+    }
+}
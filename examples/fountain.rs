@@ -39,10 +39,8 @@ fn main() -> Result<()> {
     while is_running {
         if event::poll(Duration::ZERO)? {
             match event::read()? {
-                event::Event::Key(e) => {
-                    if e.code == KeyCode::Esc {
-                        is_running = false;
-                    }
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
                 }
                 event::Event::Resize(_, _) => {
                     fm.reset();
@@ -72,16 +70,9 @@ fn main() -> Result<()> {
 }
 
 fn gen_fountain_firework(center: Vec2) -> Firework {
-    let colors = vec![(226, 196, 136), (255, 245, 253), (208, 58, 99)];
+    let colors = [(226, 196, 136), (255, 245, 253), (208, 58, 99)];
     let mut particles = Vec::new();
-    for v in gen_points_fan(
-        300.,
-        45,
-        5 as f32 / 12 as f32 * PI,
-        7 as f32 / 12 as f32 * PI,
-    )
-    .iter()
-    {
+    for v in gen_points_fan(300., 45, 5. / 12. * PI, 7. / 12. * PI).iter() {
         particles.push(ParticleConfig::new(
             center,
             *v,
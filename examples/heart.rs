@@ -39,10 +39,8 @@ fn main() -> Result<()> {
     while is_running {
         if event::poll(Duration::ZERO)? {
             match event::read()? {
-                event::Event::Key(e) => {
-                    if e.code == KeyCode::Esc {
-                        is_running = false;
-                    }
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
                 }
                 event::Event::Resize(_, _) => {
                     fm.reset();
@@ -72,7 +70,7 @@ fn main() -> Result<()> {
 }
 
 fn gen_heart_firework(center: Vec2) -> Firework {
-    let colors = vec![
+    let colors = [
         (233, 232, 237),
         (254, 142, 130),
         (200, 27, 72),
@@ -104,7 +102,7 @@ fn gen_heart_firework(center: Vec2) -> Firework {
         .with_ar_scale(0.1)
         .with_gravity_scale(0.1)
         .with_gradient_scale(gradient)
-        .with_additional_force(move |particle| (center - particle.pos) * 2.);
+        .with_additional_force(|ctx| (ctx.center - ctx.particle.pos) * 2.);
     config.set_enable_gradient(true);
     Firework {
         init_time: SystemTime::now(),
@@ -0,0 +1,101 @@
+use std::{
+    env,
+    io::{stdout, Result},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, KeyCode},
+    execute, terminal,
+};
+use firework_rs::{
+    config::Config, demo::demo_firework_image, fireworks::FireworkManager, image::Image,
+    term::Terminal,
+};
+use glam::Vec2;
+
+/// Build a pixel art heart fading from red to orange, as a stand-in for a picture file
+fn heart() -> Image {
+    let mask = [
+        " ##   ## ",
+        "#### ####",
+        "#########",
+        "#########",
+        " ####### ",
+        "  #####  ",
+        "   ###   ",
+        "    #    ",
+    ];
+    let mut pixels = Vec::new();
+    for (y, row) in mask.iter().enumerate() {
+        for c in row.chars() {
+            let g = (y * 24) as u8;
+            pixels.push(if c == '#' {
+                (255, 40 + g, 60, 255)
+            } else {
+                (0, 0, 0, 0)
+            });
+        }
+    }
+    Image::new(9, mask.len(), pixels)
+}
+
+fn main() -> Result<()> {
+    let image = match env::args().nth(1) {
+        Some(path) => Image::open(&path)?,
+        None => heart(),
+    };
+    let mut stdout = stdout();
+    let (_width, _height) = terminal::size()?;
+    let mut is_running = true;
+    let cfg = Config::default();
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let mut time = SystemTime::now();
+    let mut term = Terminal::default();
+    let mut fm = FireworkManager::default()
+        .with_firework(demo_firework_image(
+            Vec2::new(_width as f32 / 4., _height as f32 / 2.),
+            &image,
+            1000,
+            Duration::ZERO,
+            true,
+        ))
+        .enable_loop();
+
+    while is_running {
+        if event::poll(Duration::ZERO)? {
+            match event::read()? {
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
+                }
+                event::Event::Resize(_, _) => {
+                    fm.reset();
+                    term.reinit(&cfg);
+                }
+                _ => {}
+            };
+        }
+
+        let delta_time = SystemTime::now().duration_since(time).unwrap();
+        fm.update(time, delta_time);
+        time = SystemTime::now();
+
+        term.render(&fm, &cfg);
+        term.print(&mut stdout, &cfg);
+
+        if delta_time < Duration::from_secs_f32(0.05) {
+            let rem = Duration::from_secs_f32(0.05) - delta_time;
+            sleep(rem);
+        }
+    }
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    Ok(())
+}
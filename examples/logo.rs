@@ -0,0 +1,82 @@
+use std::{
+    env,
+    io::{stdout, Result},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, KeyCode},
+    execute, terminal,
+};
+use firework_rs::{
+    config::Config, demo::demo_firework_path, fireworks::FireworkManager, term::Terminal,
+    utils::parse_svg_path,
+};
+use glam::Vec2;
+
+/// A star with a curved tail, in SVG path data
+const LOGO: &str =
+    "M 50 5 L 61 38 L 95 38 L 68 58 L 78 92 L 50 72 L 22 92 L 32 58 L 5 38 L 39 38 Z \
+                    M 10 98 Q 50 80 90 98";
+
+fn main() -> Result<()> {
+    let path = env::args().nth(1).unwrap_or_else(|| LOGO.to_string());
+    let Some(polylines) = parse_svg_path(&path) else {
+        eprintln!("invalid svg path `{}`", path);
+        return Ok(());
+    };
+
+    let mut stdout = stdout();
+    let (_width, _height) = terminal::size()?;
+    let mut is_running = true;
+    let cfg = Config::default();
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let mut time = SystemTime::now();
+    let mut term = Terminal::default();
+    let mut fm = FireworkManager::default()
+        .with_firework(demo_firework_path(
+            Vec2::new(_width as f32 / 4., _height as f32 / 2.),
+            &polylines,
+            (_height as f32 / 3.).min(16.),
+            Duration::ZERO,
+            true,
+        ))
+        .enable_loop();
+
+    while is_running {
+        if event::poll(Duration::ZERO)? {
+            match event::read()? {
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
+                }
+                event::Event::Resize(_, _) => {
+                    fm.reset();
+                    term.reinit(&cfg);
+                }
+                _ => {}
+            };
+        }
+
+        let delta_time = SystemTime::now().duration_since(time).unwrap();
+        fm.update(time, delta_time);
+        time = SystemTime::now();
+
+        term.render(&fm, &cfg);
+        term.print(&mut stdout, &cfg);
+
+        if delta_time < Duration::from_secs_f32(0.05) {
+            let rem = Duration::from_secs_f32(0.05) - delta_time;
+            sleep(rem);
+        }
+    }
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    Ok(())
+}
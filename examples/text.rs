@@ -0,0 +1,70 @@
+use std::{
+    env,
+    io::{stdout, Result},
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, KeyCode},
+    execute, terminal,
+};
+use firework_rs::{
+    config::Config, demo::demo_firework_text, fireworks::FireworkManager, term::Terminal,
+};
+use glam::Vec2;
+
+fn main() -> Result<()> {
+    let mut stdout = stdout();
+    let (_width, _height) = terminal::size()?;
+    let mut is_running = true;
+    let cfg = Config::default();
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let mut time = SystemTime::now();
+    let mut term = Terminal::default();
+    let text = env::args().nth(1).unwrap_or_else(|| "RUST".to_string());
+    let mut fm = FireworkManager::default()
+        .with_firework(demo_firework_text(
+            Vec2::new(_width as f32 / 4., _height as f32 / 2.),
+            &text,
+            Duration::ZERO,
+            true,
+        ))
+        .enable_loop();
+
+    while is_running {
+        if event::poll(Duration::ZERO)? {
+            match event::read()? {
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
+                }
+                event::Event::Resize(_, _) => {
+                    fm.reset();
+                    term.reinit(&cfg);
+                }
+                _ => {}
+            };
+        }
+
+        let delta_time = SystemTime::now().duration_since(time).unwrap();
+        fm.update(time, delta_time);
+        time = SystemTime::now();
+
+        term.render(&fm, &cfg);
+        term.print(&mut stdout, &cfg);
+
+        if delta_time < Duration::from_secs_f32(0.05) {
+            let rem = Duration::from_secs_f32(0.05) - delta_time;
+            sleep(rem);
+        }
+    }
+
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    Ok(())
+}
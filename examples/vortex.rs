@@ -11,6 +11,7 @@ use crossterm::{
 };
 use firework_rs::{
     config::Config,
+    field::ForceField,
     fireworks::{ExplosionForm, Firework, FireworkConfig, FireworkManager},
     particle::ParticleConfig,
     term::Terminal,
@@ -30,18 +31,16 @@ fn main() -> Result<()> {
 
     let mut time = SystemTime::now();
     let mut term = Terminal::default();
-    let mut fm = FireworkManager::default().with_firework(gen_vortex_firework(Vec2::new(
-        _width as f32 / 4.,
-        _height as f32 / 2.,
-    )));
+    let center = Vec2::new(_width as f32 / 4., _height as f32 / 2.);
+    let mut fm = FireworkManager::default()
+        .with_firework(gen_vortex_firework(center))
+        .with_force_field(ForceField::attractor(center, 150.));
 
     while is_running {
         if event::poll(Duration::ZERO)? {
             match event::read()? {
-                event::Event::Key(e) => {
-                    if e.code == KeyCode::Esc {
-                        is_running = false;
-                    }
+                event::Event::Key(e) if e.code == KeyCode::Esc => {
+                    is_running = false;
                 }
                 event::Event::Resize(_, _) => {
                     fm.reset();
@@ -71,7 +70,7 @@ fn main() -> Result<()> {
 }
 
 fn gen_vortex_firework(center: Vec2) -> Firework {
-    let colors = vec![
+    let colors = [
         (6, 55, 63),
         (24, 90, 96),
         (47, 123, 119),
@@ -82,7 +81,7 @@ fn gen_vortex_firework(center: Vec2) -> Firework {
     for p in gen_points_circle(30, 45).iter() {
         particles.push(ParticleConfig::new(
             center + *p,
-            Vec2::new((*p).y, -(*p).x).normalize() * 15.,
+            Vec2::new(p.y, -p.x).normalize() * 15.,
             thread_rng().gen_range(28..40),
             Duration::from_secs_f32(thread_rng().gen_range(4.5..7.0)),
             *colors.iter().choose(&mut thread_rng()).unwrap(),
@@ -91,10 +90,7 @@ fn gen_vortex_firework(center: Vec2) -> Firework {
     let mut config = FireworkConfig::default()
         .with_ar_scale(0.05)
         .with_gravity_scale(0.)
-        .with_gradient_scale(gradient)
-        .with_additional_force(move |particle| {
-            (center - particle.pos).normalize() * (1. / center.distance(particle.pos)) * 150.
-        });
+        .with_gradient_scale(gradient);
     config.set_enable_gradient(true);
     Firework {
         init_time: SystemTime::now(),
@@ -1,4 +1,16 @@
-use clap::Parser;
+use std::{
+    io::ErrorKind,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use clap::{Parser, ValueEnum};
+use firework_rs::{
+    glyph::GlyphPalette,
+    palette::{self, ColorSpace, Harmony, Palette, NAMED},
+    shell::ShellKind,
+};
+use glam::Vec2;
 
 /// Used to receive command line arguments
 #[derive(Parser)]
@@ -31,4 +43,356 @@ pub struct Cli {
     /// If enabled, each character will take up two Latin character space
     #[arg(long)]
     pub cjk: bool,
+
+    /// Quit after the show has run for the given duration, e.g. `10s`, `2m`, `1m30s`, `500ms`
+    ///
+    /// A number without unit is read as seconds
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub duration: Option<Duration>,
+
+    /// Exit when all fireworks of the selected demo are gone
+    ///
+    /// Requires a demo to be selected, and can't be used together with looping
+    #[arg(long, requires = "demo", conflicts_with = "looping")]
+    pub once: bool,
+
+    /// Exit when any key is pressed, useful as a screensaver
+    #[arg(long)]
+    pub exit_on_any_key: bool,
+
+    /// Count down to the given local time, e.g. `2027-01-01T00:00:00`, then fire the finale
+    ///
+    /// The time left is shown in large digits, and the infinite random firework demo starts after the finale
+    #[arg(long, value_name = "DATE-TIME", value_parser = parse_local_time, conflicts_with_all = ["demo", "once"])]
+    pub countdown: Option<SystemTime>,
+
+    /// Show a message in large letters together with the fireworks, e.g. "HAPPY BIRTHDAY"
+    #[arg(short, long, value_name = "TEXT")]
+    pub message: Option<String>,
+
+    /// Set where the message is placed
+    #[arg(long, value_enum, value_name = "POSITION", default_value_t = MessagePosition::Bottom)]
+    pub message_position: MessagePosition,
+
+    /// Set the color of the message, e.g. `#ffd60a` or `255,214,10`
+    #[arg(long, value_name = "COLOR", value_parser = parse_color, default_value = "255,255,255")]
+    pub message_color: (u8, u8, u8),
+
+    /// Draw the message behind the fireworks instead of in front of them
+    #[arg(long)]
+    pub message_behind: bool,
+
+    /// Blow wind over the fireworks, e.g. `3,0` to the right or `-2,0.5` to the left and a bit down
+    ///
+    /// The wind comes in gusts, gravity is about 10 for comparison
+    #[arg(long, value_name = "X,Y", value_parser = parse_force, allow_hyphen_values = true)]
+    pub wind: Option<Vec2>,
+
+    /// Stir the fireworks with swirling turbulence of the given strength, e.g. `20`
+    #[arg(long, value_name = "STRENGTH", value_parser = parse_strength)]
+    pub turbulence: Option<f32>,
+
+    /// Add a ground at the bottom of the terminal, and set what happens to particles that hit it
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub ground: Option<GroundMode>,
+
+    /// Make particles bounce back from both sides of the terminal
+    #[arg(long)]
+    pub walls: bool,
+
+    /// Show the fireworks over a lake which reflects them
+    ///
+    /// Optionally set the height of the horizon as a fraction of the terminal height from the top [default: 0.75]
+    #[arg(long, value_name = "HORIZON", num_args = 0..=1, default_missing_value = "0.75", value_parser = parse_horizon)]
+    pub lake: Option<f32>,
+
+    /// Leave smoke clouds that drift with the wind and are lit by later bursts
+    ///
+    /// A firework is only gone once its smoke has cleared, so `--once` exits a few seconds later
+    #[arg(long)]
+    pub smoke: bool,
+
+    /// Set the background drawn behind the fireworks
+    #[arg(long, value_enum, value_name = "SCENE", default_value_t = SceneMode::Blank)]
+    pub scene: SceneMode,
+
+    /// Show the fireworks in 3D, seen by a slowly orbiting perspective camera
+    #[arg(long = "3d")]
+    pub three_d: bool,
+
+    /// Fire only one type of shell in the infinite random firework demo
+    #[arg(
+        long,
+        value_enum,
+        value_name = "NAME",
+        ignore_case = true,
+        conflicts_with = "demo"
+    )]
+    pub shell: Option<ShellMode>,
+
+    /// Set the colors of the infinite random firework demo
+    #[arg(long, value_name = "PALETTE", value_parser = parse_palette, long_help = palette_help())]
+    pub palette: Option<Palettes>,
+
+    /// Set the characters the fireworks are drawn with
+    ///
+    /// A palette name: ascii, cjk, kana, braille, box, emoji
+    ///
+    /// Or a file with one density band per line like `alive 0.5 /\|()` and glitter like `sparkle *+`, see `GlyphPalette::parse`
+    #[arg(long, value_name = "GLYPHS", value_parser = parse_glyphs)]
+    pub glyphs: Option<GlyphPalette>,
+}
+
+/// Where the message is placed in the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MessagePosition {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// Background drawn behind the fireworks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SceneMode {
+    /// Black screen
+    Blank,
+    /// Moon and twinkling stars
+    Night,
+    /// City skyline with lit windows under the moon and stars
+    City,
+}
+
+/// What happens to particles that hit the ground
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroundMode {
+    /// Particles bounce back
+    Bounce,
+    /// Particles splash into sparks
+    Splash,
+    /// Particles settle and fade as embers
+    Embers,
+}
+
+/// Type of shell fired by the infinite random firework demo, see `ShellKind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ShellMode {
+    /// Sphere of plain stars
+    Peony,
+    /// Sphere of stars leaving glittering trails
+    Chrysanthemum,
+    /// Long-lived golden trails drooping like willow branches
+    Willow,
+    /// A few thick arms like the leaves of a palm tree
+    Palm,
+    /// Ring of stars
+    Ring,
+    /// Two rings of stars crossing at right angles
+    DoubleRing,
+    /// Sphere of stars inside a ring
+    Saturn,
+    /// A few stars that split into crossing sparks
+    Crossette,
+    /// Stars thrown up and falling down together
+    Horsetail,
+    /// Dense glittering golden trails
+    Kamuro,
+    /// Single bright star rising from the ground
+    Comet,
+    /// Stars fired up from the ground in a fan
+    Mine,
+    /// Stars wriggling away like swimming fish
+    Fish,
+}
+
+impl From<ShellMode> for ShellKind {
+    fn from(mode: ShellMode) -> Self {
+        match mode {
+            ShellMode::Peony => ShellKind::Peony,
+            ShellMode::Chrysanthemum => ShellKind::Chrysanthemum,
+            ShellMode::Willow => ShellKind::Willow,
+            ShellMode::Palm => ShellKind::Palm,
+            ShellMode::Ring => ShellKind::Ring,
+            ShellMode::DoubleRing => ShellKind::DoubleRing,
+            ShellMode::Saturn => ShellKind::Saturn,
+            ShellMode::Crossette => ShellKind::Crossette,
+            ShellMode::Horsetail => ShellKind::Horsetail,
+            ShellMode::Kamuro => ShellKind::Kamuro,
+            ShellMode::Comet => ShellKind::Comet,
+            ShellMode::Mine => ShellKind::Mine,
+            ShellMode::Fish => ShellKind::Fish,
+        }
+    }
+}
+
+/// Parse a duration like `10s`, `2m`, `1m30s`, `500ms` or `1.5h`
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let err = || {
+        format!(
+            "invalid duration `{}`, expected something like `10s`, `2m` or `1m30s`",
+            s
+        )
+    };
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(err());
+    }
+    if let Ok(secs) = s.parse::<f32>() {
+        return Duration::try_from_secs_f32(secs).map_err(|_| err());
+    }
+    let mut total = Duration::ZERO;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .ok_or_else(err)?;
+        let unit_len = rest[num_len..]
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len() - num_len);
+        let value: f32 = rest[..num_len].parse().map_err(|_| err())?;
+        let scale = match &rest[num_len..num_len + unit_len] {
+            "ms" => 0.001,
+            "s" => 1.,
+            "m" => 60.,
+            "h" => 3600.,
+            _ => return Err(err()),
+        };
+        total += Duration::try_from_secs_f32(value * scale).map_err(|_| err())?;
+        rest = &rest[num_len + unit_len..];
+    }
+    Ok(total)
+}
+
+/// Parse a local date and time like `2027-01-01T00:00:00` or `2027-01-01 00:00`
+fn parse_local_time(s: &str) -> Result<SystemTime, String> {
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ];
+    let naive = FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s.trim(), f).ok())
+        .ok_or_else(|| {
+            format!(
+                "invalid date and time `{}`, expected something like `2027-01-01T00:00:00`",
+                s
+            )
+        })?;
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(SystemTime::from)
+        .ok_or_else(|| format!("`{}` doesn't exist in the local time zone", s))
+}
+
+/// Parse a color like `#ffd60a` or `255,214,10`
+fn parse_color(s: &str) -> Result<(u8, u8, u8), String> {
+    palette::parse_color(s).ok_or_else(|| {
+        format!(
+            "invalid color `{}`, expected something like `#ffd60a` or `255,214,10`",
+            s
+        )
+    })
+}
+
+/// Parse a horizon between 0 and 1, like `0.75`
+fn parse_horizon(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(h) if (0. ..=1.).contains(&h) => Ok(h),
+        _ => Err(format!(
+            "invalid horizon `{}`, expected a number between 0 and 1",
+            s
+        )),
+    }
+}
+
+/// Parse a force like `3,0` or `-2,0.5`
+fn parse_force(s: &str) -> Result<Vec2, String> {
+    let err = || format!("invalid force `{}`, expected something like `3,0`", s);
+    let c = s
+        .split(',')
+        .map(|v| v.trim().parse::<f32>().map_err(|_| err()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match c[..] {
+        [x, y] if x.is_finite() && y.is_finite() => Ok(Vec2::new(x, y)),
+        _ => Err(err()),
+    }
+}
+
+/// Parse a strength like `20`
+fn parse_strength(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(v) if v.is_finite() => Ok(v),
+        _ => Err(format!(
+            "invalid strength `{}`, expected a number like `20`",
+            s
+        )),
+    }
+}
+
+/// Palettes of `--palette`, a single value that may hold several palettes
+#[derive(Debug, Clone)]
+pub struct Palettes(pub Vec<Palette>);
+
+/// Return the long help of `--palette`, which lists the names of `palette::NAMED`
+fn palette_help() -> String {
+    format!(
+        "Set the colors of the infinite random firework demo\n\n\
+         A palette name: {}\n\n\
+         A harmony of a base color: `complementary:#ff5f6d`, `triadic:255,95,109` or `analogous:#ff5f6d:oklch`\n\n\
+         Or a file with one palette per line like `sunset: #ff5f6d #ffc371`, or a JSON object like \
+         `{{\"sunset\": [\"#ff5f6d\", \"#ffc371\"]}}` with the `json` feature",
+        NAMED.map(|(n, _)| n).join(", ")
+    )
+}
+
+/// Parse a palette name like `neon`, a harmony like `triadic:#ff5f6d` or `analogous:#ff5f6d:oklch`, or a palette file
+fn parse_palette(s: &str) -> Result<Palettes, String> {
+    if let Some(p) = Palette::named(s) {
+        return Ok(Palettes(vec![p]));
+    }
+    if let Some((harmony, rest)) = s.split_once(':') {
+        if let Some(harmony) = Harmony::from_name(harmony) {
+            let (color, space) = match rest.rsplit_once(':') {
+                Some((color, space)) if space.eq_ignore_ascii_case("oklch") => {
+                    (color, ColorSpace::Oklch)
+                }
+                Some((color, space)) if space.eq_ignore_ascii_case("hsl") => {
+                    (color, ColorSpace::Hsl)
+                }
+                _ => (rest, ColorSpace::Hsl),
+            };
+            return Ok(Palettes(vec![Palette::harmony(
+                parse_color(color)?,
+                harmony,
+                space,
+            )]));
+        }
+    }
+    match Palette::load(s) {
+        Ok(palettes) if !palettes.is_empty() => Ok(Palettes(palettes)),
+        Ok(_) => Err(format!("palette file `{}` has no palette", s)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Err(format!(
+            "invalid palette `{}`, expected one of {}, a harmony like `triadic:#ff5f6d`, or a palette file",
+            s,
+            NAMED.map(|(n, _)| format!("`{}`", n)).join(", ")
+        )),
+        Err(e) => Err(format!("invalid palette file `{}`, {}", s, e)),
+    }
+}
+
+/// Parse a glyph palette name like `braille`, or a glyph palette file
+fn parse_glyphs(s: &str) -> Result<GlyphPalette, String> {
+    if let Some(g) = GlyphPalette::named(s) {
+        return Ok(g);
+    }
+    GlyphPalette::load(s).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => format!(
+            "invalid glyphs `{}`, expected one of {}, or a glyph palette file",
+            s,
+            GlyphPalette::NAMES.map(|n| format!("`{}`", n)).join(", ")
+        ),
+        _ => format!("invalid glyph palette file `{}`, {}", s, e),
+    })
 }
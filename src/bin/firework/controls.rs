@@ -0,0 +1,150 @@
+use crossterm::{
+    event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    style,
+};
+use firework_rs::term::{Char, Terminal};
+
+/// Smallest and largest simulation speed that can be reached with `+`/`-`
+const SPEED_RANGE: (f32, f32) = (0.25, 4.);
+/// Factor applied to the simulation speed by one `+`/`-` key press
+const SPEED_STEP: f32 = 1.25;
+
+/// Actions that can be triggered from the keyboard while the show is running
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Quit,
+    TogglePause,
+    SpeedUp,
+    SlowDown,
+    ToggleGradient,
+    ToggleCjk,
+    /// Switch to the demo with the given number, `None` is the infinite random firework demo
+    SwitchDemo(Option<u8>),
+    Restart,
+    ToggleHelp,
+}
+
+impl Action {
+    /// Map a key event to an `Action`
+    pub fn from_key(e: KeyEvent) -> Option<Self> {
+        if e.kind == KeyEventKind::Release {
+            return None;
+        }
+        if e.modifiers.contains(KeyModifiers::CONTROL) {
+            return match e.code {
+                KeyCode::Char('c') => Some(Action::Quit),
+                _ => None,
+            };
+        }
+        match e.code {
+            KeyCode::Esc | KeyCode::Char('q') => Some(Action::Quit),
+            KeyCode::Char(' ') => Some(Action::TogglePause),
+            KeyCode::Char('+') | KeyCode::Char('=') => Some(Action::SpeedUp),
+            KeyCode::Char('-') | KeyCode::Char('_') => Some(Action::SlowDown),
+            KeyCode::Char('g') => Some(Action::ToggleGradient),
+            KeyCode::Char('c') => Some(Action::ToggleCjk),
+            KeyCode::Char(d @ '0'..='4') => Some(Action::SwitchDemo(Some(d as u8 - b'0'))),
+            KeyCode::Char('5') => Some(Action::SwitchDemo(None)),
+            KeyCode::Char('r') => Some(Action::Restart),
+            KeyCode::Char('?') | KeyCode::Char('h') => Some(Action::ToggleHelp),
+            _ => None,
+        }
+    }
+}
+
+/// Return the simulation speed after one `+` key press
+pub fn speed_up(speed: f32) -> f32 {
+    (speed * SPEED_STEP).min(SPEED_RANGE.1)
+}
+
+/// Return the simulation speed after one `-` key press
+pub fn slow_down(speed: f32) -> f32 {
+    (speed / SPEED_STEP).max(SPEED_RANGE.0)
+}
+
+/// State of the running show which is displayed in the help overlay
+pub struct Status {
+    pub demo: Option<u8>,
+    pub speed: f32,
+    pub paused: bool,
+    pub gradient: bool,
+    pub cjk: bool,
+}
+
+/// Draw the help overlay at the center of the `Terminal`
+pub fn draw_help(term: &mut Terminal, status: &Status, cjk: bool) {
+    let on_off = |b: bool| if b { "on" } else { "off" };
+    let demo = match status.demo {
+        Some(d) => d.to_string(),
+        None => "random".to_string(),
+    };
+    let lines = [
+        " Keyboard controls ".to_string(),
+        String::new(),
+        "  q / Esc / Ctrl-C   quit".to_string(),
+        "  space              pause / resume".to_string(),
+        "  + / -              speed up / slow down".to_string(),
+        "  g                  toggle gradient".to_string(),
+        "  c                  toggle cjk characters".to_string(),
+        "  0 ~ 4              switch demo".to_string(),
+        "  5                  infinite random demo".to_string(),
+        "  r                  restart show".to_string(),
+        "  ?                  toggle this help".to_string(),
+        String::new(),
+        format!(
+            "  demo: {}  speed: {:.2}x  paused: {}",
+            demo,
+            status.speed,
+            on_off(status.paused)
+        ),
+        format!(
+            "  gradient: {}  cjk: {}",
+            on_off(status.gradient),
+            on_off(status.cjk)
+        ),
+    ];
+    draw_box(term, &lines, cjk);
+}
+
+/// Draw a one-line notice at the top-left corner of the `Terminal`
+pub fn draw_notice(term: &mut Terminal, text: &str, cjk: bool) {
+    draw_text(term, 0, 0, text, style::Color::White, cjk);
+}
+
+fn draw_box(term: &mut Terminal, lines: &[String], cjk: bool) {
+    let inner = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) + 2;
+    let (w, h) = (inner + 2, lines.len() + 2);
+    let x0 = (term.size.0 as usize).saturating_sub(w) / 2;
+    let y0 = (term.size.1 as usize).saturating_sub(h) / 2;
+    let color = style::Color::White;
+    let border = format!("+{}+", "-".repeat(inner));
+    draw_text(term, x0, y0, &border, color, cjk);
+    for (i, l) in lines.iter().enumerate() {
+        let row = format!("| {:<width$} |", l, width = inner - 2);
+        draw_text(term, x0, y0 + i + 1, &row, color, cjk);
+    }
+    draw_text(term, x0, y0 + h - 1, &border, color, cjk);
+}
+
+fn draw_text(term: &mut Terminal, x: usize, y: usize, text: &str, color: style::Color, cjk: bool) {
+    let Some(line) = term.screen.get_mut(y) else {
+        return;
+    };
+    for (i, c) in text.chars().enumerate() {
+        if let Some(cell) = line.get_mut(x + i) {
+            *cell = Char {
+                text: if cjk { to_fullwidth(c) } else { c },
+                color,
+            };
+        }
+    }
+}
+
+/// Convert an ascii character to its full-width form, so that it takes up one cjk cell
+fn to_fullwidth(c: char) -> char {
+    match c {
+        ' ' => '\u{3000}',
+        '!'..='~' => char::from_u32(c as u32 - 0x21 + 0xFF01).unwrap_or(c),
+        _ => c,
+    }
+}
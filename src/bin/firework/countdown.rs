@@ -0,0 +1,72 @@
+use std::time::{Duration, SystemTime};
+
+use firework_rs::{config::Config, font::text_bitmap, term::Terminal};
+
+/// Color of the countdown digits
+const COLOR: (u8, u8, u8) = (255, 214, 10);
+/// Color of the countdown digits in the last ten seconds
+const COLOR_FINAL: (u8, u8, u8) = (242, 52, 72);
+
+/// Counts down to a target time, after which the finale is fired once
+pub struct Countdown {
+    pub target: SystemTime,
+    /// Set to `true` once the finale has been fired
+    pub fired: bool,
+}
+
+impl Countdown {
+    /// Create a new `Countdown`
+    pub fn new(target: SystemTime) -> Self {
+        Self {
+            target,
+            fired: false,
+        }
+    }
+
+    /// Return the time left before the target time, or `None` if it has been reached
+    pub fn remaining(&self, now: SystemTime) -> Option<Duration> {
+        self.target
+            .duration_since(now)
+            .ok()
+            .filter(|d| !d.is_zero())
+    }
+}
+
+/// Format the time left, the last minute is shown as seconds only
+fn format_remaining(d: Duration) -> String {
+    let secs = d.as_secs_f32().ceil() as u64;
+    if secs < 60 {
+        secs.to_string()
+    } else if secs < 3600 {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    }
+}
+
+/// Draw the time left in large digits at the center of the `Terminal`
+///
+/// Every lit pixel of the digits is drawn with characters from the glyph palettes
+pub fn draw_countdown(term: &mut Terminal, remaining: Duration, cfg: &Config) {
+    let bitmap = text_bitmap(&format_remaining(remaining), 1);
+    let color = if remaining <= Duration::from_secs(10) {
+        COLOR_FINAL
+    } else {
+        COLOR
+    };
+    let px_width = cfg.cell_aspect();
+    let (tw, th) = (term.size.0 as usize, term.size.1 as usize);
+    let scale = ((tw * 4 / 5) / (bitmap[0].len() * px_width))
+        .min((th * 3 / 5) / bitmap.len())
+        .max(1);
+    let center = ((tw / 2) as isize, (th / 2) as isize);
+    term.draw_bitmap(
+        &bitmap,
+        center,
+        (scale * px_width, scale),
+        color,
+        1.,
+        true,
+        cfg,
+    );
+}
@@ -1,80 +1,120 @@
 use std::time::Duration;
 
-use firework_rs::{config::Config, demo::demo_firework_0, fireworks::FireworkManager};
+use firework_rs::{
+    config::Config,
+    demo::{
+        demo_firework_0, demo_firework_2, demo_firework_comb_0, demo_firework_comb_1,
+        demo_firework_comb_2, demo_firework_comb_3,
+    },
+    fireworks::{Firework, FireworkManager},
+    palette::{Palette, CARNIVAL, DUSK, EMBER, GLACIER, LAGOON, PASTEL, ROYAL, WINE},
+    shell::{Shell, ShellKind},
+};
 use glam::Vec2;
 use rand::{seq::IteratorRandom, thread_rng, Rng};
 
+/// Build the `FireworkManager` of the selected demo
+///
+/// `None` builds the infinite random firework demo, an invalid demo number returns `None`
+pub fn build_show(
+    demo: Option<u8>,
+    width: u16,
+    height: u16,
+    enable_gradient: bool,
+) -> Option<FireworkManager> {
+    let fm = match demo {
+        Some(0) => FireworkManager::default().with_fireworks(demo_firework_comb_0(
+            Vec2::new(width as f32 / 4., height as f32 / 2.),
+            Duration::from_secs_f32(0.7),
+            enable_gradient,
+        )),
+        Some(1) => FireworkManager::default().with_fireworks(demo_firework_comb_2(
+            Vec2::new(width as f32 / 4., height as f32 / 2.),
+            Duration::from_secs_f32(0.7),
+            enable_gradient,
+        )),
+        Some(2) => FireworkManager::default().with_fireworks(demo_firework_comb_3(
+            Vec2::new(width as f32 / 4., height as f32 / 2.),
+            Duration::from_secs_f32(0.7),
+            enable_gradient,
+        )),
+        Some(3) => FireworkManager::default().with_fireworks(demo_firework_comb_1(
+            Vec2::new(width as f32 / 4., 66.),
+            Duration::from_secs_f32(0.2),
+            enable_gradient,
+        )),
+        Some(4) => FireworkManager::default().with_firework(demo_firework_2(
+            Vec2::new(width as f32 / 4., height as f32 / 2.),
+            Duration::from_secs_f32(0.7),
+            enable_gradient,
+        )),
+        None => FireworkManager::default().enable_dyn_install(),
+        _ => return None,
+    };
+    Some(fm)
+}
+
+/// Return the palettes of the infinite random firework demo when `--palette` is not set
+pub fn default_palettes() -> Vec<Palette> {
+    [
+        ("ember", EMBER),
+        ("carnival", CARNIVAL),
+        ("glacier", GLACIER),
+        ("lagoon", LAGOON),
+        ("pastel", PASTEL),
+        ("wine", WINE),
+        ("royal", ROYAL),
+        ("dusk", DUSK),
+    ]
+    .into_iter()
+    .map(|(name, colors)| Palette::new(name, colors.to_vec()))
+    .collect()
+}
+
+/// Return a new random `Firework` of the infinite random firework demo, or `None` if the show is full
 pub fn dyn_gen(
-    fm: &mut FireworkManager,
+    fm: &FireworkManager,
     width: u16,
     height: u16,
     enable_gradient: bool,
     cfg: &Config,
-) {
-    let colors = [
-        vec![
-            (255, 102, 75),
-            (144, 56, 67),
-            (255, 225, 124),
-            (206, 32, 41),
-        ],
-        vec![
-            (235, 39, 155),
-            (250, 216, 68),
-            (242, 52, 72),
-            (63, 52, 200),
-            (255, 139, 57),
-        ],
-        vec![
-            (152, 186, 227),
-            (89, 129, 177),
-            (54, 84, 117),
-            (240, 244, 254),
-        ],
-        vec![
-            (34, 87, 122),
-            (56, 163, 165),
-            (87, 204, 153),
-            (128, 237, 153),
-            (199, 249, 204),
-        ],
-        vec![
-            (205, 180, 219),
-            (255, 200, 221),
-            (255, 175, 204),
-            (189, 224, 254),
-            (162, 210, 255),
-        ],
-        vec![
-            (79, 0, 11),
-            (114, 0, 38),
-            (206, 66, 87),
-            (255, 127, 81),
-            (255, 155, 84),
-        ],
-        vec![(0, 29, 61), (0, 53, 102), (255, 195, 0), (255, 214, 10)],
-        vec![
-            (61, 52, 139),
-            (118, 120, 237),
-            (247, 184, 1),
-            (241, 135, 1),
-            (243, 91, 4),
-        ],
-    ];
+    shell: Option<ShellKind>,
+    palettes: &[Palette],
+) -> Option<Firework> {
     let limit = if cfg.enable_cjk {
         (width as usize * height as usize) / 1800 + 3
     } else {
         (width as usize * height as usize) / 1300 + 3
     };
-    if fm.fireworks.len() < limit {
-        let x: isize = thread_rng().gen_range(-3..(width as isize + 3));
-        let y: isize = thread_rng().gen_range(-1..(height as isize + 1));
-        fm.add_firework(demo_firework_0(
+    if fm.fireworks.len() >= limit {
+        return None;
+    }
+    let x: isize = thread_rng().gen_range(-3..(width as isize + 3));
+    let y: isize = thread_rng().gen_range(-1..(height as isize + 1));
+    let spawn_after = Duration::from_secs_f32(thread_rng().gen_range(0.0..2.0));
+    let depth = cfg.camera.is_some();
+    let colors = palettes
+        .iter()
+        .choose(&mut thread_rng())
+        .map(|p| p.colors.clone())
+        .unwrap_or_default();
+    Some(match shell {
+        // Shells fired from the ground start at the bottom
+        Some(kind) if kind.is_ground() => Shell::new(kind).with_depth(depth).build(
+            Vec2::new(x as f32, height as f32),
+            spawn_after,
+            enable_gradient,
+        ),
+        Some(kind) => Shell::new(kind)
+            .with_colors(colors)
+            .with_depth(depth)
+            .build(Vec2::new(x as f32, y as f32), spawn_after, enable_gradient),
+        None => demo_firework_0(
             Vec2::new(x as f32, y as f32),
-            Duration::from_secs_f32(thread_rng().gen_range(0.0..2.0)),
+            spawn_after,
             enable_gradient,
-            colors.iter().choose(&mut thread_rng()).unwrap().to_owned(),
+            colors,
             cfg,
-        ));
-    }
+        ),
+    })
 }
@@ -1,31 +1,35 @@
 //! With the `firework` binary, you can run some pre-designed fireworks with command line arguments
 
 mod args;
+mod controls;
+mod countdown;
 mod gen;
 
 use std::{
     io::{stdout, Error, Result},
     thread::sleep,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 
-use args::Cli;
+use args::{Cli, GroundMode, MessagePosition, SceneMode};
 use clap::Parser;
-use crossterm::{
-    cursor,
-    event::{self, KeyCode},
-    execute, terminal,
-};
-use firework_rs::term::Terminal;
-use firework_rs::{config::Config, fireworks::FireworkManager};
+use controls::{draw_help, draw_notice, slow_down, speed_up, Action, Status};
+use countdown::{draw_countdown, Countdown};
+use crossterm::{cursor, event, execute, terminal};
+use firework_rs::term::{Reflection, Terminal};
 use firework_rs::{
-    demo::{
-        demo_firework_2, demo_firework_comb_0, demo_firework_comb_1, demo_firework_comb_2,
-        demo_firework_comb_3,
-    },
-    fireworks::FireworkInstallForm,
+    boundary::{Boundary, GroundBehavior},
+    camera::Camera,
+    config::Config,
+    demo::demo_firework_finale,
+    field::ForceField,
+    fireworks::{Firework, FireworkInstallForm, FireworkManager},
+    scene::Scene,
+    shell::ShellKind,
+    smoke::SmokeConfig,
+    text::{TextLayer, TextOverlay, TextPosition},
 };
-use gen::dyn_gen;
+use gen::{build_show, default_palettes, dyn_gen};
 use glam::Vec2;
 
 fn main() -> Result<()> {
@@ -33,72 +37,73 @@ fn main() -> Result<()> {
     let mut fps: u8 = 20;
     let mut is_running = true;
     let cli = Cli::parse();
-    if cli.cjk {
-        cfg = Config { enable_cjk: true };
-    }
+    cfg.enable_cjk = cli.cjk;
+    cfg.glyphs = cli.glyphs.clone();
+    cfg.scene = match cli.scene {
+        SceneMode::Blank => Scene::Blank,
+        SceneMode::Night => Scene::Night,
+        SceneMode::City => Scene::City,
+    };
+    cfg.camera = cli.three_d.then(Camera::default);
     if let Some(f) = cli.fps {
         if !(5..=30).contains(&f) {
-            return Err(Error::new(
-                std::io::ErrorKind::Other,
-                "Invalid fps value! Valid fps range: 5~30",
-            ));
+            return Err(Error::other("Invalid fps value! Valid fps range: 5~30"));
         } else {
             fps = f;
         }
     }
     let (mut _width, mut _height) = terminal::size()?;
-    let mut fm = match cli.demo {
-        Some(0) => FireworkManager::default().with_fireworks(demo_firework_comb_0(
-            Vec2::new(_width as f32 / 4., _height as f32 / 2.),
-            Duration::from_secs_f32(0.7),
-            cli.gradient,
-        )),
-        Some(1) => FireworkManager::default().with_fireworks(demo_firework_comb_2(
-            Vec2::new(_width as f32 / 4., _height as f32 / 2.),
-            Duration::from_secs_f32(0.7),
-            cli.gradient,
-        )),
-        Some(2) => FireworkManager::default().with_fireworks(demo_firework_comb_3(
-            Vec2::new(_width as f32 / 4., _height as f32 / 2.),
-            Duration::from_secs_f32(0.7),
-            cli.gradient,
-        )),
-        Some(3) => FireworkManager::default().with_fireworks(demo_firework_comb_1(
-            Vec2::new(_width as f32 / 4., 66.),
-            Duration::from_secs_f32(0.2),
-            cli.gradient,
-        )),
-        Some(4) => FireworkManager::default().with_firework(demo_firework_2(
-            Vec2::new(_width as f32 / 4., _height as f32 / 2.),
-            Duration::from_secs_f32(0.7),
-            cli.gradient,
-        )),
-        None => FireworkManager::default().enable_dyn_install(),
-        _ => {
-            return Err(Error::new(
-                std::io::ErrorKind::Other,
-                "Invalid demo number! Demo number should be: 0~4",
-            ));
-        }
-    };
-    fm.set_enable_loop(cli.looping);
+    let mut demo = cli.demo;
+    let mut gradient = cli.gradient;
+    let mut speed: f32 = 1.;
+    let mut paused = false;
+    let mut show_help = false;
+    let mut countdown = cli.countdown.map(Countdown::new);
+    let palettes = cli.palette.clone().map_or_else(default_palettes, |p| p.0);
+    let mut fm = new_show(demo, gradient, &cli)?;
 
     let mut stdout = stdout();
     terminal::enable_raw_mode()?;
     execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
 
+    let started = Instant::now();
     let mut time = SystemTime::now();
     let mut term = Terminal::new(&cfg);
 
     while is_running {
         if event::poll(Duration::ZERO)? {
             match event::read()? {
-                event::Event::Key(e) => {
-                    if e.code == KeyCode::Esc {
-                        is_running = false;
-                    }
+                event::Event::Key(e)
+                    if cli.exit_on_any_key && e.kind != event::KeyEventKind::Release =>
+                {
+                    is_running = false;
                 }
-                event::Event::Resize(_, _) => {
+                event::Event::Key(e) => match Action::from_key(e) {
+                    Some(Action::Quit) => is_running = false,
+                    Some(Action::TogglePause) => paused = !paused,
+                    Some(Action::SpeedUp) => speed = speed_up(speed),
+                    Some(Action::SlowDown) => speed = slow_down(speed),
+                    Some(Action::ToggleGradient) => {
+                        gradient = !gradient;
+                        fm.fireworks
+                            .iter_mut()
+                            .for_each(|f| f.config.set_enable_gradient(gradient));
+                    }
+                    Some(Action::ToggleCjk) => {
+                        cfg.enable_cjk = !cfg.enable_cjk;
+                        term.reinit(&cfg);
+                        fm = new_show(demo, gradient, &cli)?;
+                    }
+                    Some(Action::SwitchDemo(d)) => {
+                        demo = d;
+                        fm = new_show(demo, gradient, &cli)?;
+                    }
+                    Some(Action::Restart) => fm = new_show(demo, gradient, &cli)?,
+                    Some(Action::ToggleHelp) => show_help = !show_help,
+                    None => {}
+                },
+                event::Event::Resize(w, h) => {
+                    fm.boundary = boundary(&cli, w, h);
                     fm.reset();
                     term.reinit(&cfg);
                 }
@@ -108,24 +113,84 @@ fn main() -> Result<()> {
 
         (_width, _height) = terminal::size()?;
         let delta_time = SystemTime::now().duration_since(time).unwrap();
-        if fm.install_form == FireworkInstallForm::DynamicInstall {
-            dyn_gen(
-                &mut fm,
+        let sim_delta = if paused {
+            Duration::ZERO
+        } else {
+            delta_time.mul_f32(speed)
+        };
+        let remaining = countdown
+            .as_ref()
+            .filter(|c| !c.fired)
+            .map(|c| c.remaining(SystemTime::now()));
+        if let Some(None) = remaining {
+            // Time is up, fire the finale which is followed by the random show
+            let (w, h) = (term.size.0 as f32, term.size.1 as f32);
+            let w = if cfg.enable_cjk { w } else { w / 2. };
+            let mut finale = demo_firework_finale(
+                Vec2::new(w / 2., h / 2.),
+                Vec2::new(w * 0.35, h * 0.25),
+                Duration::ZERO,
+                gradient,
+            );
+            finale.iter_mut().for_each(|f| prepare(f, &cli));
+            fm.add_fireworks(finale);
+            if let Some(c) = countdown.as_mut() {
+                c.fired = true;
+            }
+        }
+        if fm.install_form == FireworkInstallForm::DynamicInstall && !paused && remaining.is_none()
+        {
+            let firework = dyn_gen(
+                &fm,
                 if cfg.enable_cjk {
                     (_width - 1) / 2
                 } else {
                     _width
                 },
                 _height,
-                cli.gradient,
+                gradient,
                 &cfg,
+                cli.shell.map(ShellKind::from),
+                &palettes,
             );
+            if let Some(mut firework) = firework {
+                prepare(&mut firework, &cli);
+                fm.add_firework(firework);
+            }
+        }
+        shift_schedule(&mut fm, delta_time, sim_delta);
+        if !paused {
+            fm.update(time, sim_delta);
         }
-        fm.update(time, delta_time);
         time = SystemTime::now();
         term.render(&fm, &cfg);
+        if let Some(horizon) = cli.lake {
+            term.render_reflection(&Reflection::new(horizon), fm.time_elapsed);
+        }
+        if let Some(Some(d)) = remaining {
+            draw_countdown(&mut term, d, &cfg);
+        }
+        if show_help {
+            let status = Status {
+                demo,
+                speed,
+                paused,
+                gradient,
+                cjk: cfg.enable_cjk,
+            };
+            draw_help(&mut term, &status, cfg.enable_cjk);
+        } else if paused {
+            draw_notice(&mut term, " PAUSED - press ? for help ", cfg.enable_cjk);
+        }
         term.print(&mut stdout, &cfg);
 
+        if cli.once && fm.is_finished() {
+            is_running = false;
+        }
+        if cli.duration.is_some_and(|d| started.elapsed() >= d) {
+            is_running = false;
+        }
+
         if delta_time < Duration::from_secs_f32(1. / fps as f32) {
             let rem = Duration::from_secs_f32(1. / fps as f32) - delta_time;
             sleep(rem);
@@ -137,3 +202,95 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// Build a fresh `FireworkManager` of the given demo, sized to the current terminal
+fn new_show(demo: Option<u8>, gradient: bool, cli: &Cli) -> Result<FireworkManager> {
+    let (width, height) = terminal::size()?;
+    let mut fm = build_show(demo, width, height, gradient)
+        .ok_or_else(|| Error::other("Invalid demo number! Demo number should be: 0~4"))?;
+    fm.set_enable_loop(cli.looping);
+    fm.fireworks.iter_mut().for_each(|f| prepare(f, cli));
+    fm.boundary = boundary(cli, width, height);
+    if let Some(wind) = cli.wind {
+        fm.add_force_field(ForceField::gusty_wind(wind, 0.5, Duration::from_secs(3)));
+    }
+    if let Some(strength) = cli.turbulence {
+        fm.add_force_field(ForceField::turbulence(strength));
+    }
+    if let Some(message) = &cli.message {
+        let position = match cli.message_position {
+            MessagePosition::Top => TextPosition::Top,
+            MessagePosition::Center => TextPosition::Center,
+            MessagePosition::Bottom => TextPosition::Bottom,
+        };
+        fm.add_text(
+            TextOverlay::new(message.as_str())
+                .with_position(position)
+                .with_color(cli.message_color)
+                .with_layer(if cli.message_behind {
+                    TextLayer::Back
+                } else {
+                    TextLayer::Front
+                })
+                .with_scale(2),
+        );
+    }
+    Ok(fm)
+}
+
+/// Apply the options of the binary to a `Firework` as it is added to the show
+fn prepare(firework: &mut Firework, cli: &Cli) {
+    // Flat bursts become spheres, bursts already moving in depth are kept as they are
+    if cli.three_d && firework.particles.iter().all(|p| p.init_vel_z == 0.) {
+        firework.lift_to_3d();
+    }
+    // Fireworks of the show keep their own smoke
+    if cli.smoke && firework.config.smoke.is_none() {
+        firework.config.smoke = Some(SmokeConfig::default());
+    }
+}
+
+/// Build the ground and walls of the terminal selected by `--ground` and `--walls`, the ground is the horizon of `--lake`
+fn boundary(cli: &Cli, width: u16, height: u16) -> Boundary {
+    let mut boundary = Boundary::default();
+    if let Some(mode) = cli.ground {
+        let behavior = match mode {
+            GroundMode::Bounce => GroundBehavior::Bounce {
+                restitution: 0.5,
+                friction: 0.2,
+            },
+            GroundMode::Splash => GroundBehavior::Splash {
+                sparks: 3,
+                speed: 0.3,
+                min_speed: 8.,
+            },
+            GroundMode::Embers => GroundBehavior::Settle {
+                ember_time: Duration::from_secs_f32(1.5),
+            },
+        };
+        // With a lake, particles hit the water at the horizon
+        let ground = cli
+            .lake
+            .map_or(height as f32, |h| (height as f32 * h).round());
+        boundary = boundary.with_ground(ground - 1., behavior);
+    }
+    if cli.walls {
+        // Every particle takes up two columns, in cjk mode as well
+        boundary = boundary.with_walls(0., width as f32 / 2. - 1., 0.6);
+    }
+    boundary
+}
+
+/// Move the spawn time of all `Firework`s so that they stay in step with the simulation clock
+///
+/// `Firework`s spawn according to the wall clock, so when the show is paused or its speed is changed,
+/// their `init_time` is moved by the difference between the wall clock and the simulation clock
+fn shift_schedule(fm: &mut FireworkManager, wall_delta: Duration, sim_delta: Duration) {
+    for f in fm.fireworks.iter_mut() {
+        if wall_delta > sim_delta {
+            f.init_time += wall_delta - sim_delta;
+        } else {
+            f.init_time -= sim_delta - wall_delta;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! `boundary` module provides the ground and walls that particles collide with
+
+use std::time::Duration;
+
+use glam::Vec2;
+
+/// Ground and side walls of a `FireworkManager`
+///
+/// By default there is neither ground nor walls, and `Particle`s fall until their lifetime ends
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Boundary {
+    pub ground: Option<Ground>,
+    pub walls: Option<Walls>,
+}
+
+/// Horizontal line that `Particle`s can't go below
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ground {
+    /// `y` of the ground line, `y` axis points down
+    pub y: f32,
+    /// What happens to `Particle`s that hit the ground
+    pub behavior: GroundBehavior,
+}
+
+/// What happens to a `Particle` when it hits the `Ground`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroundBehavior {
+    /// `Particle` bounces back
+    ///
+    /// `restitution` is the part of vertical speed kept after a bounce, `friction` the part of horizontal speed lost
+    Bounce { restitution: f32, friction: f32 },
+    /// `Particle` dies and throws up `sparks` smaller `Particle`s
+    ///
+    /// Sparks fly up with `speed` times the speed of the impact, and sparks slower than `min_speed` die without splashing
+    Splash {
+        sparks: usize,
+        speed: f32,
+        min_speed: f32,
+    },
+    /// `Particle` stops on the ground and fades as an ember for at most `ember_time`
+    Settle { ember_time: Duration },
+}
+
+/// Vertical walls on both sides, which `Particle`s bounce back from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Walls {
+    /// `x` of the left wall
+    pub left: f32,
+    /// `x` of the right wall
+    pub right: f32,
+    /// Part of horizontal speed kept after a bounce
+    pub restitution: f32,
+}
+
+/// Result of a collision with the `Ground` that changes the life of a `Particle`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Collision {
+    /// `Particle` has settled on the ground and lives at most `ember_time` more
+    Settle { ember_time: Duration },
+    /// `Particle` dies and throws up sparks, `vel` is its velocity at the impact
+    Splash { vel: Vec2 },
+    /// `Particle` dies quietly
+    Absorb,
+}
+
+impl Boundary {
+    /// Add a `Ground` at `y`
+    #[inline]
+    #[must_use]
+    pub fn with_ground(mut self, y: f32, behavior: GroundBehavior) -> Self {
+        self.ground = Some(Ground { y, behavior });
+        self
+    }
+
+    /// Add `Walls` at `left` and `right`
+    #[inline]
+    #[must_use]
+    pub fn with_walls(mut self, left: f32, right: f32, restitution: f32) -> Self {
+        self.walls = Some(Walls {
+            left,
+            right,
+            restitution,
+        });
+        self
+    }
+
+    /// Return true if there is neither ground nor walls
+    pub fn is_empty(&self) -> bool {
+        self.ground.is_none() && self.walls.is_none()
+    }
+
+    /// Keep a `Particle` inside the boundary, bouncing it back from walls and ground
+    ///
+    /// Return the `Collision` if the `Particle` has to settle or die
+    pub(crate) fn collide(&self, pos: &mut Vec2, vel: &mut Vec2) -> Option<Collision> {
+        if let Some(walls) = &self.walls {
+            if pos.x < walls.left {
+                pos.x = walls.left;
+                vel.x = vel.x.abs() * walls.restitution;
+            } else if pos.x > walls.right {
+                pos.x = walls.right;
+                vel.x = -vel.x.abs() * walls.restitution;
+            }
+        }
+        let ground = self.ground.as_ref()?;
+        if pos.y < ground.y {
+            return None;
+        }
+        pos.y = ground.y;
+        match ground.behavior {
+            GroundBehavior::Bounce {
+                restitution,
+                friction,
+            } => {
+                vel.y = -vel.y.abs() * restitution;
+                vel.x *= 1. - friction;
+                None
+            }
+            GroundBehavior::Splash { min_speed, .. } => {
+                let impact = *vel;
+                *vel = Vec2::ZERO;
+                if impact.length() >= min_speed {
+                    Some(Collision::Splash { vel: impact })
+                } else {
+                    Some(Collision::Absorb)
+                }
+            }
+            GroundBehavior::Settle { ember_time } => {
+                *vel = Vec2::ZERO;
+                Some(Collision::Settle { ember_time })
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! `camera` module provides the perspective camera of the 3D mode
+
+use std::time::Duration;
+
+use glam::{Quat, Vec2, Vec3};
+
+/// Perspective camera looking at the fireworks, set by `Config::camera`
+///
+/// Without a `Camera` the fireworks are drawn flat, `Particle`s' depth is ignored
+///
+/// # Notes
+///
+/// - The camera looks at the center of the screen at depth 0, and slowly orbits around it
+/// - Farther `Particle`s are drawn smaller and dimmer, and nearer ones are drawn over them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    /// Distance from the camera to the center of the screen, shorter gives stronger perspective
+    pub distance: f32,
+    /// Angular speed in radians per second of the orbit around the vertical axis
+    pub orbit_speed: f32,
+    /// How much farther `Particle`s are dimmed, 0 doesn't dim them
+    pub depth_dimming: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            distance: 120.,
+            orbit_speed: 0.1,
+            depth_dimming: 1.5,
+        }
+    }
+}
+
+impl Camera {
+    /// Create a new `Camera` at `distance` from the center of the screen
+    pub fn new(distance: f32) -> Self {
+        Self {
+            distance,
+            ..Default::default()
+        }
+    }
+
+    /// Set `orbit_speed`
+    #[inline]
+    #[must_use]
+    pub fn with_orbit_speed(mut self, orbit_speed: f32) -> Self {
+        self.orbit_speed = orbit_speed;
+        self
+    }
+
+    /// Set `depth_dimming`
+    #[inline]
+    #[must_use]
+    pub fn with_depth_dimming(mut self, depth_dimming: f32) -> Self {
+        self.depth_dimming = depth_dimming;
+        self
+    }
+
+    /// Project a point on the screen plane
+    ///
+    /// Return the projected point and its depth seen from the camera, 0 at the center of the screen,
+    /// or `None` if the point is behind the camera
+    ///
+    /// # Arguments
+    ///
+    /// * `pivot` - Center of the screen which the camera orbits around
+    /// * `time` - Time since the orbit started, e.g. `FireworkManager::time_elapsed`
+    pub fn project(&self, point: Vec3, pivot: Vec2, time: Duration) -> Option<(Vec2, f32)> {
+        let rel = Quat::from_rotation_y(time.as_secs_f32() * self.orbit_speed)
+            * (point - pivot.extend(0.));
+        let z = rel.z + self.distance;
+        if z < self.distance * 0.05 {
+            return None;
+        }
+        Some((pivot + rel.truncate() * self.distance / z, rel.z))
+    }
+
+    /// Return the brightness scale of a point at `depth` returned by `project`
+    pub fn brightness(&self, depth: f32) -> f32 {
+        (self.distance / (self.distance + depth))
+            .powf(self.depth_dimming)
+            .clamp(0.2, 1.2)
+    }
+}
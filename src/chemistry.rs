@@ -0,0 +1,139 @@
+//! `chemistry` module provides physically inspired colors of burning metal salts and hot glowing bodies
+//!
+//! Set `ParticleConfig::with_emission` and the color of the `Particle` shifts as it cools over its life
+
+/// Metal salts that give firework stars their colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetalSalt {
+    /// Strontium carbonate, deep red
+    Strontium,
+    /// Calcium chloride, orange
+    Calcium,
+    /// Sodium nitrate, bright yellow
+    Sodium,
+    /// Barium nitrate, green
+    Barium,
+    /// Copper chloride, blue
+    Copper,
+    /// Strontium and copper together, purple
+    Violet,
+    /// Titanium powder, white sparks of hot metal
+    Titanium,
+}
+
+/// How a `Particle` emits light, set by `ParticleConfig::with_emission`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Emission {
+    /// `ParticleConfig::color` for the whole life
+    #[default]
+    Fixed,
+    /// Star colored by a burning metal salt, white-hot when lit and fading to a dull ember as it burns out
+    Salt(MetalSalt),
+    /// Hot body glowing at `temperature` kelvins when it is born, cooling down over its life
+    ///
+    /// Charcoal sparks glow at about 1800 K, hot metal sparks at 2500 K to 3500 K
+    Blackbody { temperature: f32 },
+}
+
+/// Temperature in kelvins the star residue cools down to as a `Particle` dies
+const EMBER_TEMPERATURE: f32 = 1100.;
+
+impl MetalSalt {
+    /// All the `MetalSalt`s
+    pub const ALL: [MetalSalt; 7] = [
+        MetalSalt::Strontium,
+        MetalSalt::Calcium,
+        MetalSalt::Sodium,
+        MetalSalt::Barium,
+        MetalSalt::Copper,
+        MetalSalt::Violet,
+        MetalSalt::Titanium,
+    ];
+
+    /// Return the color of the flame of the `MetalSalt`
+    pub fn color(&self) -> (u8, u8, u8) {
+        match self {
+            MetalSalt::Strontium => (255, 28, 36),
+            MetalSalt::Calcium => (255, 120, 20),
+            MetalSalt::Sodium => (255, 214, 40),
+            MetalSalt::Barium => (90, 255, 70),
+            MetalSalt::Copper => (40, 110, 255),
+            MetalSalt::Violet => (170, 60, 255),
+            MetalSalt::Titanium => (240, 240, 255),
+        }
+    }
+}
+
+impl Emission {
+    /// Return the color of a `Particle` of color `base` at `life_fraction` of its life, from 0 to 1
+    pub fn color(&self, base: (u8, u8, u8), life_fraction: f32) -> (u8, u8, u8) {
+        let t = life_fraction.clamp(0., 1.);
+        match self {
+            Emission::Fixed => base,
+            // Titanium sparks are hot metal, so they glow and cool like a blackbody
+            Emission::Salt(MetalSalt::Titanium) => {
+                Emission::Blackbody { temperature: 5000. }.color(base, t)
+            }
+            Emission::Salt(salt) => {
+                let flame = salt.color();
+                if t < 0.1 {
+                    // Freshly lit stars burn white-hot before the salt colors the flame
+                    mix(blackbody_color(4500.), flame, t / 0.1)
+                } else if t > 0.75 {
+                    // Burnt out stars leave a glowing residue
+                    mix(
+                        flame,
+                        blackbody_color(EMBER_TEMPERATURE + 600.),
+                        (t - 0.75) / 0.25,
+                    )
+                } else {
+                    flame
+                }
+            }
+            Emission::Blackbody { temperature } => {
+                blackbody_color(cooling_temperature(*temperature, t))
+            }
+        }
+    }
+}
+
+/// Return the temperature in kelvins at `life_fraction` of the life of a body `temperature` kelvins hot when it is born
+///
+/// The body cools exponentially towards `EMBER_TEMPERATURE` like Newton's law of cooling
+pub fn cooling_temperature(temperature: f32, life_fraction: f32) -> f32 {
+    let ambient = EMBER_TEMPERATURE.min(temperature);
+    ambient + (temperature - ambient) * (-3. * life_fraction.clamp(0., 1.)).exp()
+}
+
+/// Return the color of a blackbody glowing at `temperature` kelvins, from 1000 K to 40000 K
+///
+/// Colors are at full brightness, dim them with the gradient of the `Firework`
+pub fn blackbody_color(temperature: f32) -> (u8, u8, u8) {
+    // Fit of the Planckian locus in sRGB by Tanner Helland
+    let t = temperature.clamp(1000., 40000.) / 100.;
+    let r = if t <= 66. {
+        255.
+    } else {
+        329.698_73 * (t - 60.).powf(-0.133_204_76)
+    };
+    let g = if t <= 66. {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_16 * (t - 60.).powf(-0.075_514_85)
+    };
+    let b = if t >= 66. {
+        255.
+    } else if t <= 19. {
+        0.
+    } else {
+        138.517_73 * (t - 10.).ln() - 305.044_8
+    };
+    let to_u8 = |v: f32| v.clamp(0., 255.) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+fn mix(a: (u8, u8, u8), b: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let t = t.clamp(0., 1.);
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    (lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+}
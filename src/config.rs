@@ -1,5 +1,73 @@
+use crate::{camera::Camera, glyph::GlyphPalette, scene::Scene, utils::cell_aspect};
+
+static ASCII_GLYPHS: GlyphPalette = GlyphPalette::ASCII;
+static CJK_GLYPHS: GlyphPalette = GlyphPalette::CJK;
+
 /// Configuration of the program
 #[derive(Default)]
 pub struct Config {
     pub enable_cjk: bool,
+    /// Background drawn behind the fireworks
+    pub scene: Scene,
+    /// Perspective camera of the 3D mode, `None` draws the fireworks flat
+    pub camera: Option<Camera>,
+    /// Characters the fireworks are drawn with, `None` picks the ascii or cjk palette by `enable_cjk`
+    pub glyphs: Option<GlyphPalette>,
+}
+
+impl Config {
+    /// Create a new `Config`, everything but `enable_cjk` is default
+    pub fn new(enable_cjk: bool) -> Self {
+        Self {
+            enable_cjk,
+            ..Default::default()
+        }
+    }
+
+    /// Set `scene`
+    #[inline]
+    #[must_use]
+    pub fn with_scene(mut self, scene: Scene) -> Self {
+        self.scene = scene;
+        self
+    }
+
+    /// Set `camera`
+    #[inline]
+    #[must_use]
+    pub fn with_camera(mut self, camera: Camera) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    /// Set `glyphs`
+    #[inline]
+    #[must_use]
+    pub fn with_glyphs(mut self, glyphs: GlyphPalette) -> Self {
+        self.glyphs = Some(glyphs);
+        self
+    }
+
+    /// Return the `GlyphPalette` of the program, see `glyphs`
+    pub fn glyphs(&self) -> &GlyphPalette {
+        match &self.glyphs {
+            Some(glyphs) => glyphs,
+            None if self.enable_cjk => &CJK_GLYPHS,
+            None => &ASCII_GLYPHS,
+        }
+    }
+
+    /// Return the width of a cell in terminal columns, 2 when cjk is enabled and 1 otherwise
+    pub fn cell_width(&self) -> usize {
+        if self.enable_cjk {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Return how many cells make a square, see `utils::cell_aspect`
+    pub fn cell_aspect(&self) -> usize {
+        cell_aspect(self.cell_width())
+    }
 }
@@ -11,11 +11,17 @@ use rand::{seq::IteratorRandom, thread_rng, Rng};
 use crate::{
     config::Config,
     fireworks::{ExplosionForm, Firework, FireworkConfig},
+    image::Image,
+    palette::{
+        AMBER, AZURE, CARNIVAL, CHAMPAGNE, EMBER, GLACIER, GOLD, HARBOR, MARIGOLD, MEADOW,
+        MIDNIGHT, OCEAN, ORCHID, ROSE, SILVER, TEAL,
+    },
     particle::ParticleConfig,
     utils::{
         explosion_gradient_1, explosion_gradient_2, explosion_gradient_3, gen_points_arc,
         gen_points_circle, gen_points_circle_normal, gen_points_circle_normal_dev, gen_points_fan,
-        linear_gradient_1,
+        gen_points_image, gen_points_polylines, gen_points_text, gen_shape_velocities,
+        linear_gradient_1, shape_hold_force,
     },
 };
 
@@ -58,12 +64,7 @@ pub fn demo_firework_0(
 }
 
 pub fn demo_firework_1(center: Vec2, spawn_after: Duration, enable_gradient: bool) -> Firework {
-    let colors = [
-        (255, 102, 75),
-        (144, 56, 67),
-        (255, 225, 124),
-        (206, 32, 41),
-    ];
+    let colors = EMBER;
     let mut particles = Vec::new();
     for v in gen_points_circle_normal(250., 45).iter() {
         particles.push(ParticleConfig::new(
@@ -87,7 +88,7 @@ pub fn demo_firework_1(center: Vec2, spawn_after: Duration, enable_gradient: boo
 }
 
 pub fn demo_firework_2(center: Vec2, spawn_after: Duration, enable_gradient: bool) -> Firework {
-    let colors = [(250, 216, 68)];
+    let colors = GOLD;
     let mut particles = Vec::new();
     for v in gen_points_circle(100, 600).iter() {
         particles.push(ParticleConfig::new(
@@ -114,12 +115,7 @@ pub fn demo_firework_2(center: Vec2, spawn_after: Duration, enable_gradient: boo
 }
 
 pub fn demo_firework_3(center: Vec2, spawn_after: Duration, enable_gradient: bool) -> Firework {
-    let colors = [
-        (242, 233, 190),
-        (226, 196, 136),
-        (149, 202, 176),
-        (26, 64, 126),
-    ];
+    let colors = HARBOR;
     let mut particles = Vec::new();
     for v in gen_points_circle_normal(350., 135).iter() {
         particles.push(ParticleConfig::new(
@@ -146,7 +142,7 @@ pub fn demo_firework_3(center: Vec2, spawn_after: Duration, enable_gradient: boo
 }
 
 pub fn demo_firework_4(center: Vec2, spawn_after: Duration, enable_gradient: bool) -> Firework {
-    let colors = [(242, 233, 190), (226, 196, 136), (255, 248, 253)];
+    let colors = CHAMPAGNE;
     let mut particles = Vec::new();
     for v in gen_points_circle_normal(350., 25).iter() {
         particles.push(ParticleConfig::new(
@@ -172,7 +168,7 @@ pub fn demo_firework_4(center: Vec2, spawn_after: Duration, enable_gradient: boo
 }
 
 pub fn demo_firework_5(center: Vec2, spawn_after: Duration, enable_gradient: bool) -> Firework {
-    let colors = [(152, 186, 227), (54, 84, 117), (21, 39, 60)];
+    let colors = MIDNIGHT;
     let mut particles = Vec::new();
     for v in gen_points_circle_normal(450., 80).iter() {
         particles.push(ParticleConfig::new(
@@ -198,7 +194,7 @@ pub fn demo_firework_5(center: Vec2, spawn_after: Duration, enable_gradient: boo
 }
 
 pub fn demo_firework_6(center: Vec2, spawn_after: Duration, enable_gradient: bool) -> Firework {
-    let colors = [(242, 233, 190), (226, 196, 136), (255, 248, 253)];
+    let colors = CHAMPAGNE;
     let mut particles = Vec::new();
     for v in gen_points_circle_normal(350., 35).iter() {
         particles.push(ParticleConfig::new(
@@ -244,13 +240,7 @@ pub fn demo_firework_comb_1(
     config1.set_enable_gradient(enable_gradient);
 
     // Explosion
-    let color2 = [
-        (235, 39, 155),
-        (250, 216, 68),
-        (242, 52, 72),
-        (63, 52, 200),
-        (255, 139, 57),
-    ];
+    let color2 = CARNIVAL;
     let center2 = start + Vec2::NEG_Y * 53.;
     let mut particles2 = Vec::new();
     for v in gen_points_circle_normal(350., 160).iter() {
@@ -324,7 +314,7 @@ pub fn demo_firework_comb_2(
 ) -> Vec<Firework> {
     let mut res = Vec::new();
     let fountain1 = |center: Vec2, angle: f32| {
-        let colors = [(255, 183, 3), (251, 133, 0), (242, 233, 190)];
+        let colors = MARIGOLD;
         let mut particles = Vec::new();
         for v in gen_points_fan(60., 20, angle - 0.05, angle + 0.05).iter() {
             particles.push(ParticleConfig::new(
@@ -355,7 +345,7 @@ pub fn demo_firework_comb_2(
     };
 
     let fountain2 = |center: Vec2| {
-        let colors = [(226, 196, 136), (255, 245, 253), (208, 58, 99)];
+        let colors = ROSE;
         let mut particles = Vec::new();
         for v in gen_points_fan(1000., 20, 5.7 / 12. * PI, 6.3 / 12. * PI).iter() {
             particles.push(ParticleConfig::new(
@@ -419,7 +409,7 @@ pub fn demo_firework_comb_2(
         res.push(mono(
             Vec2::new(center.x + i as f32, center.y + 21.),
             Duration::from_secs_f32(3.5),
-            vec![(0, 119, 182), (144, 224, 239), (12, 180, 216)],
+            AZURE.to_vec(),
         ))
     });
 
@@ -427,7 +417,7 @@ pub fn demo_firework_comb_2(
         res.push(mono(
             Vec2::new(center.x + i as f32, center.y + 21.),
             Duration::from_secs_f32(4.7),
-            vec![(181, 23, 158), (247, 37, 133), (114, 9, 183)],
+            ORCHID.to_vec(),
         ))
     });
 
@@ -435,7 +425,7 @@ pub fn demo_firework_comb_2(
         res.push(mono(
             Vec2::new(center.x + i as f32, center.y + 21.),
             Duration::from_secs_f32(5.9),
-            vec![(217, 237, 146), (153, 217, 140), (82, 182, 154)],
+            MEADOW.to_vec(),
         ))
     });
 
@@ -449,7 +439,7 @@ pub fn demo_firework_comb_3(
 ) -> Vec<Firework> {
     let mut res = Vec::new();
     let f1 = {
-        let colors = [(255, 216, 190), (255, 238, 221), (248, 247, 255)];
+        let colors = SILVER;
         let mut particles = Vec::new();
         for v in gen_points_circle_normal_dev(14., 200, 60.).iter() {
             particles.push(ParticleConfig::new(
@@ -476,12 +466,7 @@ pub fn demo_firework_comb_3(
     };
     res.push(f1);
     let f2 = {
-        let colors = [
-            (152, 186, 227),
-            (89, 129, 177),
-            (54, 84, 117),
-            (240, 244, 254),
-        ];
+        let colors = GLACIER;
         let mut particles = Vec::new();
         for v in gen_points_circle_normal_dev(10000., 600, 30.).iter() {
             particles.push(ParticleConfig::new(
@@ -509,7 +494,7 @@ pub fn demo_firework_comb_3(
     res.push(f2);
 
     for (idx, p) in gen_points_circle(27, 10).iter().enumerate() {
-        let colors = [(17, 138, 178), (6, 214, 160), (7, 59, 76), (255, 255, 255)];
+        let colors = TEAL;
         let mut particles = Vec::new();
         for v in gen_points_circle_normal_dev(100., 35, 350. / 9.).iter() {
             particles.push(ParticleConfig::new(
@@ -537,3 +522,160 @@ pub fn demo_firework_comb_3(
 
     res
 }
+
+/// An escalating finale made of waves of the demo fireworks
+///
+/// Every wave has more fireworks than the last one and follows it more closely, the finale ends with a salvo that bursts all at once
+///
+/// # Arguments
+///
+/// * `center` - Center of the area where the fireworks burst
+/// * `spread` - Half of the width and height of that area
+pub fn demo_firework_finale(
+    center: Vec2,
+    spread: Vec2,
+    spawn_after: Duration,
+    enable_gradient: bool,
+) -> Vec<Firework> {
+    let shells: [fn(Vec2, Duration, bool) -> Firework; 5] = [
+        demo_firework_1,
+        demo_firework_3,
+        demo_firework_4,
+        demo_firework_5,
+        demo_firework_6,
+    ];
+    let mut rng = thread_rng();
+    let mut res = Vec::new();
+    let mut t = 0.;
+    for wave in 0..6 {
+        for _ in 0..2 + 2 * wave {
+            let pos = center
+                + Vec2::new(
+                    rng.gen_range(-spread.x..=spread.x),
+                    rng.gen_range(-spread.y..=spread.y),
+                );
+            let shell = shells.iter().choose(&mut rng).unwrap();
+            res.push(shell(
+                pos,
+                spawn_after + Duration::from_secs_f32(t + rng.gen_range(0.0..0.8)),
+                enable_gradient,
+            ));
+        }
+        t += 1.5 - 0.2 * wave as f32;
+    }
+    for _ in 0..12 {
+        let pos = center
+            + Vec2::new(
+                rng.gen_range(-spread.x..=spread.x),
+                rng.gen_range(-spread.y..=spread.y),
+            );
+        res.push(demo_firework_3(
+            pos,
+            spawn_after + Duration::from_secs_f32(t + rng.gen_range(0.0..0.3)),
+            enable_gradient,
+        ));
+    }
+    res
+}
+
+/// A firework that bursts into a text written with the built-in bitmap font
+///
+/// The text holds in the sky for a moment before falling down
+pub fn demo_firework_text(
+    center: Vec2,
+    text: &str,
+    spawn_after: Duration,
+    enable_gradient: bool,
+) -> Firework {
+    shape_firework(
+        center,
+        &with_random_colors(gen_points_text(text, 1.5), AMBER),
+        spawn_after,
+        enable_gradient,
+    )
+}
+
+/// A firework that bursts into the outline of polylines, e.g. a logo parsed by `parse_svg_path`
+///
+/// The outline is `radius` wide from its center, and holds in the sky for a moment before falling down
+pub fn demo_firework_path(
+    center: Vec2,
+    polylines: &[Vec<Vec2>],
+    radius: f32,
+    spawn_after: Duration,
+    enable_gradient: bool,
+) -> Firework {
+    // About one point every 1.5 cells along the outline
+    let n = (radius * 6.).max(1.) as usize;
+    shape_firework(
+        center,
+        &with_random_colors(gen_points_polylines(polylines, n, radius), OCEAN),
+        spawn_after,
+        enable_gradient,
+    )
+}
+
+/// A firework that bursts into a picture, each `Particle` colored by its pixel, like a drone show
+///
+/// The picture is down-sampled to at most `n` `Particle`s, see `gen_points_image`
+pub fn demo_firework_image(
+    center: Vec2,
+    image: &Image,
+    n: usize,
+    spawn_after: Duration,
+    enable_gradient: bool,
+) -> Firework {
+    shape_firework(
+        center,
+        &gen_points_image(image, n, 1.),
+        spawn_after,
+        enable_gradient,
+    )
+}
+
+fn with_random_colors(points: Vec<Vec2>, colors: &[(u8, u8, u8)]) -> Vec<(Vec2, (u8, u8, u8))> {
+    points
+        .into_iter()
+        .map(|p| (p, *colors.iter().choose(&mut thread_rng()).unwrap()))
+        .collect()
+}
+
+/// A firework that bursts into `targets` of their own colors and holds the shape for a moment
+fn shape_firework(
+    center: Vec2,
+    targets: &[(Vec2, (u8, u8, u8))],
+    spawn_after: Duration,
+    enable_gradient: bool,
+) -> Firework {
+    const AR_SCALE: f32 = 0.08;
+    const GRAVITY_SCALE: f32 = 0.2;
+    const ARRIVE_AFTER: f32 = 0.8;
+    let points = targets.iter().map(|(p, _)| *p).collect::<Vec<_>>();
+    let mut particles = Vec::new();
+    for (v, (_, color)) in gen_shape_velocities(&points, AR_SCALE, ARRIVE_AFTER)
+        .iter()
+        .zip(targets)
+    {
+        particles.push(ParticleConfig::new(
+            center,
+            *v,
+            thread_rng().gen_range(4..7),
+            Duration::from_secs_f32(thread_rng().gen_range(3.2..3.6)),
+            *color,
+        ));
+    }
+    let mut config = FireworkConfig::default()
+        .with_gradient_scale(linear_gradient_1)
+        .with_ar_scale(AR_SCALE)
+        .with_gravity_scale(GRAVITY_SCALE)
+        .with_additional_force(shape_hold_force(ARRIVE_AFTER, 1.2, GRAVITY_SCALE));
+    config.set_enable_gradient(enable_gradient);
+    Firework {
+        init_time: SystemTime::now(),
+        spawn_after,
+        center,
+        particles,
+        config,
+        ..Default::default()
+    }
+}
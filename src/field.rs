@@ -0,0 +1,238 @@
+//! `field` module provides force fields applied to all the fireworks of a `FireworkManager`
+
+use std::time::Duration;
+
+use glam::Vec2;
+
+/// Function of position, velocity and simulation time returning a force
+pub type CustomField = Box<dyn Fn(Vec2, Vec2, Duration) -> Vec2 + Send + Sync>;
+
+/// Force applied to every `Particle` of a `FireworkManager` besides gravity and air resistance
+///
+/// Add several `ForceField`s to a `FireworkManager` to combine them, their forces are added together
+///
+/// # Notes
+///
+/// - Forces are accelerations like gravity, which is `10. * gravity_scale` downwards
+/// - `y` axis points down, as on the screen
+pub enum ForceField {
+    /// Wind blowing with `force`
+    ///
+    /// Its strength changes smoothly by up to `gust` times, over about `gust_period`
+    Wind {
+        force: Vec2,
+        gust: f32,
+        gust_period: Duration,
+    },
+    /// Swirls made of curl noise, which change over time
+    ///
+    /// `scale` is the size of the swirls, and `speed` is how many times they change per second
+    Turbulence {
+        strength: f32,
+        scale: f32,
+        speed: f32,
+    },
+    /// Pulls `Particle`s to `center` with a force of `strength / distance`, pushes them away if `strength` is negative
+    ///
+    /// Force stops growing within `radius` of `center`
+    Attractor {
+        center: Vec2,
+        strength: f32,
+        radius: f32,
+    },
+    /// Turns `Particle`s around `center` with a force of `strength / distance`, clockwise on screen if `strength` is positive
+    ///
+    /// Force stops growing within `radius` of `center`
+    Vortex {
+        center: Vec2,
+        strength: f32,
+        radius: f32,
+    },
+    /// Any force of position, velocity and simulation time
+    Custom(CustomField),
+}
+
+impl ForceField {
+    /// Create a steady `Wind`
+    pub fn wind(force: Vec2) -> Self {
+        Self::Wind {
+            force,
+            gust: 0.,
+            gust_period: Duration::from_secs(2),
+        }
+    }
+
+    /// Create a `Wind` with gusts
+    pub fn gusty_wind(force: Vec2, gust: f32, gust_period: Duration) -> Self {
+        Self::Wind {
+            force,
+            gust,
+            gust_period,
+        }
+    }
+
+    /// Create a `Turbulence` with swirls of 10 cells, changing every 2 seconds
+    pub fn turbulence(strength: f32) -> Self {
+        Self::Turbulence {
+            strength,
+            scale: 10.,
+            speed: 0.5,
+        }
+    }
+
+    /// Create an `Attractor`
+    pub fn attractor(center: Vec2, strength: f32) -> Self {
+        Self::Attractor {
+            center,
+            strength,
+            radius: 1.,
+        }
+    }
+
+    /// Create an `Attractor` that pushes `Particle`s away from `center`
+    pub fn repulsor(center: Vec2, strength: f32) -> Self {
+        Self::attractor(center, -strength)
+    }
+
+    /// Create a `Vortex`
+    pub fn vortex(center: Vec2, strength: f32) -> Self {
+        Self::Vortex {
+            center,
+            strength,
+            radius: 1.,
+        }
+    }
+
+    /// Create a `Custom` field
+    pub fn custom(f: impl Fn(Vec2, Vec2, Duration) -> Vec2 + Send + Sync + 'static) -> Self {
+        Self::Custom(Box::new(f))
+    }
+
+    /// Return the force at `pos` on a `Particle` moving at `vel`, `time` after the show started
+    pub fn force(&self, pos: Vec2, vel: Vec2, time: Duration) -> Vec2 {
+        match self {
+            ForceField::Wind {
+                force,
+                gust,
+                gust_period,
+            } => {
+                if *gust == 0. {
+                    return *force;
+                }
+                let t = time.as_secs_f32() / gust_period.as_secs_f32().max(f32::EPSILON);
+                *force * (1. + gust * (2. * perlin(t, 0.5, 0.5)).clamp(-1., 1.))
+            }
+            ForceField::Turbulence {
+                strength,
+                scale,
+                speed,
+            } => {
+                // Curl of a noise potential has no divergence, so it swirls without gathering particles
+                const EPS: f32 = 0.01;
+                let p = pos / scale.max(f32::EPSILON);
+                let t = time.as_secs_f32() * speed;
+                let dx = perlin(p.x + EPS, p.y, t) - perlin(p.x - EPS, p.y, t);
+                let dy = perlin(p.x, p.y + EPS, t) - perlin(p.x, p.y - EPS, t);
+                Vec2::new(dy, -dx) / (2. * EPS) * *strength
+            }
+            ForceField::Attractor {
+                center,
+                strength,
+                radius,
+            } => {
+                let d = *center - pos;
+                let dist = d.length();
+                if dist == 0. {
+                    return Vec2::ZERO;
+                }
+                d / dist * *strength / dist.max(*radius)
+            }
+            ForceField::Vortex {
+                center,
+                strength,
+                radius,
+            } => {
+                let d = pos - *center;
+                let dist = d.length();
+                if dist == 0. {
+                    return Vec2::ZERO;
+                }
+                d.perp() / dist * *strength / dist.max(*radius)
+            }
+            ForceField::Custom(f) => f(pos, vel, time),
+        }
+    }
+}
+
+/// Return the sum of forces of all `fields`
+pub fn total_force(fields: &[ForceField], pos: Vec2, vel: Vec2, time: Duration) -> Vec2 {
+    fields
+        .iter()
+        .fold(Vec2::ZERO, |acc, f| acc + f.force(pos, vel, time))
+}
+
+/// Smooth gradient noise in 3D, roughly between -1 and 1
+fn perlin(x: f32, y: f32, z: f32) -> f32 {
+    let (xi, yi, zi) = (x.floor(), y.floor(), z.floor());
+    let (xf, yf, zf) = (x - xi, y - yi, z - zi);
+    // Far coordinates saturate, the lattice wraps around there instead of overflowing
+    let (xi, yi, zi) = (xi as i32, yi as i32, zi as i32);
+    let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+    let corner = |dx: i32, dy: i32, dz: i32| {
+        grad(
+            hash(
+                xi.wrapping_add(dx),
+                yi.wrapping_add(dy),
+                zi.wrapping_add(dz),
+            ),
+            xf - dx as f32,
+            yf - dy as f32,
+            zf - dz as f32,
+        )
+    };
+    lerp(
+        w,
+        lerp(
+            v,
+            lerp(u, corner(0, 0, 0), corner(1, 0, 0)),
+            lerp(u, corner(0, 1, 0), corner(1, 1, 0)),
+        ),
+        lerp(
+            v,
+            lerp(u, corner(0, 0, 1), corner(1, 0, 1)),
+            lerp(u, corner(0, 1, 1), corner(1, 1, 1)),
+        ),
+    )
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32 {
+    a + t * (b - a)
+}
+
+/// Hash of a lattice point, so the noise needs no table and no random generator
+fn hash(x: i32, y: i32, z: i32) -> u32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ (z as u32).wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5bd1_e995);
+    h ^ (h >> 15)
+}
+
+/// Dot product of the offset with one of the 12 gradients of Perlin's improved noise
+fn grad(h: u32, x: f32, y: f32, z: f32) -> f32 {
+    let h = h & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
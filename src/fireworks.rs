@@ -1,14 +1,30 @@
 //! `firework` module provides functions to define, create and update fireworks
 
-use std::{
-    collections::VecDeque,
-    time::{Duration, SystemTime},
-};
+use std::time::{Duration, SystemTime};
 
 use glam::Vec2;
-use rand::{seq::IteratorRandom, thread_rng};
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::{
+    boundary::Boundary,
+    field::ForceField,
+    glyph::GlyphPalette,
+    integrator::Integrator,
+    particle::{ForceContext, LifeCurve, Particle, ParticleConfig, ParticleRng},
+    smoke::SmokeConfig,
+    store::{ParticleStore, Steps},
+    text::TextOverlay,
+};
+
+/// Default `Duration` of a simulation step of `FireworkManager`
+pub const DEFAULT_TIME_STEP: Duration = Duration::from_millis(1);
+/// `time_step` is never shorter than this to avoid an endless update
+const MIN_TIME_STEP: Duration = Duration::from_micros(10);
 
-use crate::particle::{LifeState, Particle, ParticleConfig};
+/// Function of a `ForceContext` returning the additional force applied to its `Particle`
+///
+/// It is `Send + Sync` so that `Particle`s can be updated on several threads with the `rayon` feature
+pub type AdditionalForce = Box<dyn Fn(&mut ForceContext) -> Vec2 + Send + Sync>;
 
 /// Struct representing a single firework
 pub struct Firework {
@@ -22,7 +38,9 @@ pub struct Firework {
     pub config: FireworkConfig,
     pub form: ExplosionForm,
     pub particles: Vec<ParticleConfig>,
-    pub current_particles: Vec<Particle>,
+    pub current_particles: ParticleStore,
+    /// Smoke puffs left by the explosions, empty unless `FireworkConfig::smoke` is set
+    pub smoke: ParticleStore,
 }
 
 impl Default for Firework {
@@ -36,35 +54,61 @@ impl Default for Firework {
             config: FireworkConfig::default(),
             form: ExplosionForm::Instant { used: false },
             particles: Vec::new(),
-            current_particles: Vec::new(),
+            current_particles: ParticleStore::new(),
+            smoke: ParticleStore::new(),
         }
     }
 }
 
 impl Firework {
-    /// Update the `Firework`
+    /// Advance the `Firework` by `steps`
+    ///
+    /// Trails are not recorded here, call `record_trails` once per rendered frame
     ///
     /// # Arguments
     ///
-    /// * `now` - `SystemTime` of now
-    /// * `delta_time` - `Duration` since last update
-    pub fn update(&mut self, now: SystemTime, delta_time: Duration) {
-        // Spawn particles
-        if now >= self.init_time + self.spawn_after {
-            self.time_elapsed += delta_time;
+    /// * `now` - `SystemTime` of the beginning of the first step
+    /// * `steps` - Simulation steps to go through
+    pub fn update(&mut self, now: SystemTime, steps: &Steps) {
+        let time_step = steps.dt;
+        let steps = &Steps {
+            center: self.center,
+            firework_age: self.time_elapsed,
+            ..*steps
+        };
+        // Move the particles spawned before, dead particles are cleaned meanwhile
+        self.current_particles.advance(steps, &self.config);
+        // Smoke drifts with the force fields but goes through the boundary
+        let smoke_physics = self.config.smoke.map(|s| s.physics());
+        let smoke_steps = Steps {
+            boundary: &Boundary::default(),
+            ..*steps
+        };
+        if let Some(physics) = &smoke_physics {
+            self.smoke.advance(&smoke_steps, physics);
+        }
+
+        // Spawn particles, each new particle moves for the rest of the steps
+        for k in 0..steps.count {
+            if now + time_step * k < self.init_time + self.spawn_after {
+                continue;
+            }
+            let rest = Steps {
+                firework_age: self.time_elapsed,
+                ..steps.skip(k)
+            };
+            let before = self.time_elapsed;
+            self.time_elapsed += time_step;
+            let mut rng = self.rng(self.time_elapsed);
+            let mut puff = false;
             match &mut self.form {
                 ExplosionForm::Instant { used } => {
                     if !*used {
-                        self.particles.iter().for_each(|p| {
-                            self.current_particles.push(Particle {
-                                pos: p.init_pos,
-                                vel: p.init_vel,
-                                trail: init_trail(p.init_pos, p.trail_length),
-                                life_state: LifeState::Alive,
-                                time_elapsed: Duration::ZERO,
-                                config: *p,
-                            })
-                        })
+                        for p in self.particles.iter() {
+                            let i = self.current_particles.spawn(*p);
+                            self.current_particles.advance_slot(i, &rest, &self.config);
+                        }
+                        puff = true;
                     }
                     *used = true;
                 }
@@ -74,47 +118,50 @@ impl Firework {
                     timer,
                 } => {
                     if self.time_elapsed <= *lasts {
-                        if *timer + delta_time <= *time_interval {
-                            *timer += delta_time;
+                        // A cloud when emission starts and every time a multiple of `interval` is passed
+                        puff = self.config.smoke.is_some_and(|s| {
+                            let interval = s.interval.as_nanos();
+                            interval > 0
+                                && (before.is_zero()
+                                    || before.as_nanos() / interval
+                                        < self.time_elapsed.as_nanos() / interval)
+                        });
+                        if *timer + time_step <= *time_interval {
+                            *timer += time_step;
                         } else {
-                            let n =
-                                (*timer + delta_time).as_millis() / (*time_interval).as_millis();
-                            self.particles
-                                .iter()
-                                .choose_multiple(&mut thread_rng(), n as usize)
-                                .iter()
-                                .for_each(|p| {
-                                    self.current_particles.push(Particle {
-                                        pos: p.init_pos,
-                                        vel: p.init_vel,
-                                        trail: init_trail(p.init_pos, p.trail_length),
-                                        life_state: LifeState::Alive,
-                                        time_elapsed: Duration::ZERO,
-                                        config: **p,
-                                    })
-                                });
+                            let n = (*timer + time_step).as_millis() / (*time_interval).as_millis();
+                            for p in self.particles.iter().choose_multiple(&mut rng, n as usize) {
+                                let i = self.current_particles.spawn(*p);
+                                self.current_particles.advance_slot(i, &rest, &self.config);
+                            }
                             *timer = Duration::from_millis(
-                                ((*timer + delta_time).as_millis() % (*time_interval).as_millis())
+                                ((*timer + time_step).as_millis() % (*time_interval).as_millis())
                                     as u64,
                             );
                         }
                     }
                 }
             }
+            if let (true, Some(smoke), Some(physics)) = (puff, &self.config.smoke, &smoke_physics) {
+                let rest = Steps {
+                    boundary: &Boundary::default(),
+                    ..rest
+                };
+                for p in smoke.gen_puffs(self.center, &mut rng) {
+                    let i = self.smoke.spawn(p);
+                    self.smoke.advance_slot(i, &rest, physics);
+                }
+            }
             self.state = FireworkState::Alive;
         }
 
-        self.current_particles
-            .iter_mut()
-            .for_each(|p| p.update(delta_time, &self.config));
-
-        // Clean the dead pariticles
-        self.current_particles
-            .retain(|p| p.life_state != LifeState::Dead);
-
         match self.form {
             ExplosionForm::Instant { used } => {
-                if used && self.state == FireworkState::Alive && self.current_particles.is_empty() {
+                if used
+                    && self.state == FireworkState::Alive
+                    && self.current_particles.is_empty()
+                    && self.smoke.is_empty()
+                {
                     self.state = FireworkState::Gone;
                 }
             }
@@ -122,6 +169,7 @@ impl Firework {
                 if self.time_elapsed > lasts
                     && self.state == FireworkState::Alive
                     && self.current_particles.is_empty()
+                    && self.smoke.is_empty()
                 {
                     self.state = FireworkState::Gone;
                 }
@@ -129,6 +177,41 @@ impl Firework {
         }
     }
 
+    /// Turn a flat burst into a sphere, for the 3D mode
+    ///
+    /// Every `ParticleConfig` without velocity in depth gets one, as if its velocity were the projection of a sphere
+    /// as fast as the fastest `Particle`, so the burst looks the same from the front when it starts
+    /// `ParticleConfig`s that already move in depth are kept
+    pub fn lift_to_3d(&mut self) {
+        let mut rng = self.rng(self.time_elapsed);
+        let max = self
+            .particles
+            .iter()
+            .map(|p| p.init_vel.length())
+            .fold(0., f32::max);
+        for p in self.particles.iter_mut().filter(|p| p.init_vel_z == 0.) {
+            let z = (max * max - p.init_vel.length_squared()).max(0.).sqrt();
+            p.init_vel_z = if rng.gen_bool(0.5) { z } else { -z };
+        }
+    }
+
+    /// Return a `ParticleRng` seeded from the `Firework` and the simulation time `time`
+    ///
+    /// Emission draws from it instead of `thread_rng`, so the same show gives the same result on any thread
+    fn rng(&self, time: Duration) -> ParticleRng {
+        let particle = Particle {
+            pos: self.center,
+            config: self.particles.first().copied().unwrap_or_default(),
+            ..Default::default()
+        };
+        ParticleRng::new(&particle, self.particles.len(), time)
+    }
+
+    /// Record current positions of all the `Particle`s in their trails
+    pub fn record_trails(&mut self) {
+        self.current_particles.record_trails();
+    }
+
     /// Return true if the `FireworkState` is `Gone`
     pub fn is_gone(&self) -> bool {
         self.state == FireworkState::Gone
@@ -139,7 +222,8 @@ impl Firework {
         self.init_time = SystemTime::now();
         self.state = FireworkState::Waiting;
         self.time_elapsed = Duration::ZERO;
-        self.current_particles = Vec::new();
+        self.current_particles.clear();
+        self.smoke.clear();
         match &mut self.form {
             ExplosionForm::Instant { used } => {
                 *used = false;
@@ -191,7 +275,8 @@ pub struct FireworkConfig {
     /// Air resistance scale
     /// Warning: too large or too small `ar_scale` may lead to unexpected behavior of `Particles`
     pub ar_scale: f32,
-    pub additional_force: Box<dyn Fn(&Particle) -> Vec2>,
+    /// Force applied to every `Particle` besides gravity and air resistance, `None` means no additional force
+    pub additional_force: Option<AdditionalForce>,
     /// This field is a function that takes a float between 0 and 1, returns a float representing all `Particle`s' gradient
     ///
     /// `Particle`s' gradient changes according to its elapsed time and lifetime
@@ -205,6 +290,14 @@ pub struct FireworkConfig {
     /// - It is recommanded that your terminal window is non-transparent and has black bg color to get better visual effects
     /// - Otherwise set it to `false`
     pub enable_gradient: bool,
+    /// Numerical method used to move `Particle`s
+    pub integrator: Integrator,
+    /// Smoke left at the explosion site, `None` means no smoke
+    pub smoke: Option<SmokeConfig>,
+    /// Characters the `Particle`s are drawn with, `None` uses `Config::glyphs`
+    pub glyphs: Option<GlyphPalette>,
+    /// How the `Particle`s age, unless their `ParticleConfig::life_curve` is set
+    pub life_curve: LifeCurve,
 }
 
 impl Default for FireworkConfig {
@@ -212,9 +305,13 @@ impl Default for FireworkConfig {
         Self {
             gravity_scale: 1.,
             ar_scale: 0.28,
-            additional_force: Box::new(move |_| Vec2::ZERO),
+            additional_force: None,
             gradient_scale: |_| 1.,
             enable_gradient: false,
+            integrator: Integrator::default(),
+            smoke: None,
+            glyphs: None,
+            life_curve: LifeCurve::default(),
         }
     }
 }
@@ -247,11 +344,51 @@ impl FireworkConfig {
     /// Set `additional_force`
     #[inline]
     #[must_use]
-    pub fn with_additional_force(mut self, af: impl Fn(&Particle) -> Vec2 + 'static) -> Self {
-        self.additional_force = Box::new(af);
+    pub fn with_additional_force(
+        mut self,
+        af: impl Fn(&mut ForceContext) -> Vec2 + Send + Sync + 'static,
+    ) -> Self {
+        self.additional_force = Some(Box::new(af));
+        self
+    }
+
+    /// Set `integrator`
+    #[inline]
+    #[must_use]
+    pub fn with_integrator(mut self, integrator: Integrator) -> Self {
+        self.integrator = integrator;
+        self
+    }
+
+    /// Set `smoke`
+    #[inline]
+    #[must_use]
+    pub fn with_smoke(mut self, smoke: SmokeConfig) -> Self {
+        self.smoke = Some(smoke);
+        self
+    }
+
+    /// Set `glyphs`
+    #[inline]
+    #[must_use]
+    pub fn with_glyphs(mut self, glyphs: GlyphPalette) -> Self {
+        self.glyphs = Some(glyphs);
         self
     }
 
+    /// Set `life_curve`
+    #[inline]
+    #[must_use]
+    pub fn with_life_curve(mut self, life_curve: LifeCurve) -> Self {
+        self.life_curve = life_curve;
+        self
+    }
+
+    /// Return the `LifeCurve` of a `Particle` of this `Firework`, its own if set or `life_curve`
+    pub fn life_curve_of(&self, particle: &ParticleConfig) -> LifeCurve {
+        particle.life_curve.unwrap_or(self.life_curve)
+    }
+
     /// Set `enable_gradient`
     pub fn set_enable_gradient(&mut self, enable_gradient: bool) {
         self.enable_gradient = enable_gradient;
@@ -265,6 +402,20 @@ pub struct FireworkManager {
     pub enable_loop: bool,
     /// Controls how fireworks are installed in `FireworkManager`
     pub install_form: FireworkInstallForm,
+    /// Messages drawn in large letters together with the fireworks
+    pub texts: Vec<TextOverlay>,
+    /// Forces applied to all the `Firework`s, such as wind and turbulence
+    pub force_fields: Vec<ForceField>,
+    /// Ground and walls that `Particle`s of all the `Firework`s collide with
+    pub boundary: Boundary,
+    /// `Duration` of a simulation step
+    ///
+    /// The simulation always moves forward in steps of `time_step`, whatever the frame rate is
+    pub time_step: Duration,
+    /// Simulation time that has not been simulated yet, which is less than `time_step`
+    pub accumulator: Duration,
+    /// Simulation time since the show started, which drives `ForceField`s that change over time
+    pub time_elapsed: Duration,
 }
 
 impl Default for FireworkManager {
@@ -273,6 +424,12 @@ impl Default for FireworkManager {
             fireworks: Vec::new(),
             enable_loop: false,
             install_form: FireworkInstallForm::StaticInstall,
+            texts: Vec::new(),
+            force_fields: Vec::new(),
+            boundary: Boundary::default(),
+            time_step: DEFAULT_TIME_STEP,
+            accumulator: Duration::ZERO,
+            time_elapsed: Duration::ZERO,
         }
     }
 }
@@ -284,6 +441,12 @@ impl FireworkManager {
             fireworks,
             enable_loop: false,
             install_form: FireworkInstallForm::StaticInstall,
+            texts: Vec::new(),
+            force_fields: Vec::new(),
+            boundary: Boundary::default(),
+            time_step: DEFAULT_TIME_STEP,
+            accumulator: Duration::ZERO,
+            time_elapsed: Duration::ZERO,
         }
     }
 
@@ -297,6 +460,16 @@ impl FireworkManager {
         self.fireworks.append(&mut fireworks);
     }
 
+    /// Add a `ForceField` to a existing `FireworkManager`
+    pub fn add_force_field(&mut self, field: ForceField) {
+        self.force_fields.push(field);
+    }
+
+    /// Add a `TextOverlay` to a existing `FireworkManager`
+    pub fn add_text(&mut self, text: TextOverlay) {
+        self.texts.push(text);
+    }
+
     /// Add a `Firework` to `FireworkManager`
     #[inline]
     #[must_use]
@@ -313,6 +486,38 @@ impl FireworkManager {
         self
     }
 
+    /// Add a `ForceField` to `FireworkManager`
+    #[inline]
+    #[must_use]
+    pub fn with_force_field(mut self, field: ForceField) -> Self {
+        self.force_fields.push(field);
+        self
+    }
+
+    /// Set `boundary`
+    #[inline]
+    #[must_use]
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Add a `TextOverlay` to `FireworkManager`
+    #[inline]
+    #[must_use]
+    pub fn with_text(mut self, text: TextOverlay) -> Self {
+        self.texts.push(text);
+        self
+    }
+
+    /// Set `time_step`
+    #[inline]
+    #[must_use]
+    pub fn with_time_step(mut self, time_step: Duration) -> Self {
+        self.time_step = time_step;
+        self
+    }
+
     /// Set `enable_loop` to `true`
     #[inline]
     #[must_use]
@@ -331,22 +536,63 @@ impl FireworkManager {
 
     /// Reset the whole fireworks show
     pub fn reset(&mut self) {
+        self.accumulator = Duration::ZERO;
+        self.time_elapsed = Duration::ZERO;
         for ele in self.fireworks.iter_mut() {
             ele.reset();
         }
+        for ele in self.texts.iter_mut() {
+            ele.reset();
+        }
     }
 
     pub fn set_enable_loop(&mut self, enable_loop: bool) {
         self.enable_loop = enable_loop;
     }
 
-    /// The main update function
+    /// The main update function, call it once per rendered frame
+    ///
+    /// `delta_time` is added to `accumulator`, which is consumed in fixed steps of `time_step`,
+    /// so the trajectories of `Particle`s don't depend on the frame rate
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - `SystemTime` of the beginning of the frame
+    /// * `delta_time` - `Duration` since last update
     pub fn update(&mut self, now: SystemTime, delta_time: Duration) {
+        let time_step = self.time_step.max(MIN_TIME_STEP);
+        self.accumulator += delta_time;
+        let count = (self.accumulator.as_nanos() / time_step.as_nanos()) as u32;
+        self.accumulator -= time_step * count;
+        let steps = Steps {
+            time: self.time_elapsed,
+            dt: time_step,
+            count,
+            fields: &self.force_fields,
+            boundary: &self.boundary,
+            center: Vec2::ZERO,
+            firework_age: Duration::ZERO,
+        };
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            self.fireworks.par_iter_mut().for_each(|ele| {
+                ele.update(now, &steps);
+                ele.record_trails();
+            });
+        }
+        #[cfg(not(feature = "rayon"))]
         for ele in self.fireworks.iter_mut() {
-            ele.update(now, delta_time);
+            ele.update(now, &steps);
+            ele.record_trails();
+        }
+        self.time_elapsed += time_step * count;
+        for ele in self.texts.iter_mut() {
+            ele.update(delta_time);
         }
         if self.install_form == FireworkInstallForm::DynamicInstall {
             self.fireworks.retain(|f| f.state != FireworkState::Gone);
+            self.texts.retain(|t| !t.is_gone());
         }
         if self.install_form == FireworkInstallForm::StaticInstall
             && self.enable_loop
@@ -356,6 +602,16 @@ impl FireworkManager {
         }
     }
 
+    /// Return true if the fireworks show has come to an end
+    ///
+    /// A show ends when it doesn't loop and all of its `Firework`s are `Gone`, `TextOverlay`s are not taken into account
+    /// A `FireworkManager` with `DynamicInstall` never ends, because fireworks can be added at any time
+    pub fn is_finished(&self) -> bool {
+        self.install_form == FireworkInstallForm::StaticInstall
+            && !self.enable_loop
+            && self.fireworks.iter().all(|f| f.is_gone())
+    }
+
     /// Set `install_form` to `DynamicInstall`
     pub fn enable_dyn_install(mut self) -> Self {
         self.install_form = FireworkInstallForm::DynamicInstall;
@@ -375,7 +631,3 @@ pub enum FireworkInstallForm {
     StaticInstall,
     DynamicInstall,
 }
-
-fn init_trail(init_pos: Vec2, n: usize) -> VecDeque<Vec2> {
-    VecDeque::from(vec![init_pos; n])
-}
@@ -0,0 +1,229 @@
+//! `font` module provides a built-in 5x7 bitmap font used to draw large text and text-shaped fireworks
+
+/// Width of a single glyph in pixels
+pub const GLYPH_WIDTH: usize = 5;
+/// Height of a single glyph in pixels
+pub const GLYPH_HEIGHT: usize = 7;
+
+/// Return the bitmap of a character, each row is a string where `#` marks a lit pixel
+///
+/// Lowercase letters are drawn as uppercase, unsupported characters return `None`
+pub fn glyph(c: char) -> Option<[&'static str; GLYPH_HEIGHT]> {
+    let g = match c.to_ascii_uppercase() {
+        ' ' => [
+            "     ", "     ", "     ", "     ", "     ", "     ", "     ",
+        ],
+        '0' => [
+            " ### ", "#   #", "#  ##", "# # #", "##  #", "#   #", " ### ",
+        ],
+        '1' => [
+            "  #  ", " ##  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### ",
+        ],
+        '2' => [
+            " ### ", "#   #", "    #", "   # ", "  #  ", " #   ", "#####",
+        ],
+        '3' => [
+            "#####", "   # ", "  #  ", "   # ", "    #", "#   #", " ### ",
+        ],
+        '4' => [
+            "   # ", "  ## ", " # # ", "#  # ", "#####", "   # ", "   # ",
+        ],
+        '5' => [
+            "#####", "#    ", "#### ", "    #", "    #", "#   #", " ### ",
+        ],
+        '6' => [
+            "  ## ", " #   ", "#    ", "#### ", "#   #", "#   #", " ### ",
+        ],
+        '7' => [
+            "#####", "    #", "   # ", "  #  ", " #   ", " #   ", " #   ",
+        ],
+        '8' => [
+            " ### ", "#   #", "#   #", " ### ", "#   #", "#   #", " ### ",
+        ],
+        '9' => [
+            " ### ", "#   #", "#   #", " ####", "    #", "   # ", " ##  ",
+        ],
+        'A' => [
+            " ### ", "#   #", "#   #", "#####", "#   #", "#   #", "#   #",
+        ],
+        'B' => [
+            "#### ", "#   #", "#   #", "#### ", "#   #", "#   #", "#### ",
+        ],
+        'C' => [
+            " ### ", "#   #", "#    ", "#    ", "#    ", "#   #", " ### ",
+        ],
+        'D' => [
+            "###  ", "#  # ", "#   #", "#   #", "#   #", "#  # ", "###  ",
+        ],
+        'E' => [
+            "#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#####",
+        ],
+        'F' => [
+            "#####", "#    ", "#    ", "#### ", "#    ", "#    ", "#    ",
+        ],
+        'G' => [
+            " ### ", "#   #", "#    ", "# ###", "#   #", "#   #", " ####",
+        ],
+        'H' => [
+            "#   #", "#   #", "#   #", "#####", "#   #", "#   #", "#   #",
+        ],
+        'I' => [
+            " ### ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", " ### ",
+        ],
+        'J' => [
+            "  ###", "   # ", "   # ", "   # ", "   # ", "#  # ", " ##  ",
+        ],
+        'K' => [
+            "#   #", "#  # ", "# #  ", "##   ", "# #  ", "#  # ", "#   #",
+        ],
+        'L' => [
+            "#    ", "#    ", "#    ", "#    ", "#    ", "#    ", "#####",
+        ],
+        'M' => [
+            "#   #", "## ##", "# # #", "# # #", "#   #", "#   #", "#   #",
+        ],
+        'N' => [
+            "#   #", "#   #", "##  #", "# # #", "#  ##", "#   #", "#   #",
+        ],
+        'O' => [
+            " ### ", "#   #", "#   #", "#   #", "#   #", "#   #", " ### ",
+        ],
+        'P' => [
+            "#### ", "#   #", "#   #", "#### ", "#    ", "#    ", "#    ",
+        ],
+        'Q' => [
+            " ### ", "#   #", "#   #", "#   #", "# # #", "#  # ", " ## #",
+        ],
+        'R' => [
+            "#### ", "#   #", "#   #", "#### ", "# #  ", "#  # ", "#   #",
+        ],
+        'S' => [
+            " ####", "#    ", "#    ", " ### ", "    #", "    #", "#### ",
+        ],
+        'T' => [
+            "#####", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "  #  ",
+        ],
+        'U' => [
+            "#   #", "#   #", "#   #", "#   #", "#   #", "#   #", " ### ",
+        ],
+        'V' => [
+            "#   #", "#   #", "#   #", "#   #", "#   #", " # # ", "  #  ",
+        ],
+        'W' => [
+            "#   #", "#   #", "#   #", "# # #", "# # #", "# # #", " # # ",
+        ],
+        'X' => [
+            "#   #", "#   #", " # # ", "  #  ", " # # ", "#   #", "#   #",
+        ],
+        'Y' => [
+            "#   #", "#   #", " # # ", "  #  ", "  #  ", "  #  ", "  #  ",
+        ],
+        'Z' => [
+            "#####", "    #", "   # ", "  #  ", " #   ", "#    ", "#####",
+        ],
+        '!' => [
+            "  #  ", "  #  ", "  #  ", "  #  ", "  #  ", "     ", "  #  ",
+        ],
+        '?' => [
+            " ### ", "#   #", "    #", "   # ", "  #  ", "     ", "  #  ",
+        ],
+        '.' => [
+            "     ", "     ", "     ", "     ", "     ", " ##  ", " ##  ",
+        ],
+        ',' => [
+            "     ", "     ", "     ", "     ", " ##  ", "  #  ", " #   ",
+        ],
+        ':' => [
+            "     ", " ##  ", " ##  ", "     ", " ##  ", " ##  ", "     ",
+        ],
+        '\'' => [
+            "  #  ", "  #  ", " #   ", "     ", "     ", "     ", "     ",
+        ],
+        '"' => [
+            " # # ", " # # ", "     ", "     ", "     ", "     ", "     ",
+        ],
+        '-' => [
+            "     ", "     ", "     ", "#####", "     ", "     ", "     ",
+        ],
+        '+' => [
+            "     ", "  #  ", "  #  ", "#####", "  #  ", "  #  ", "     ",
+        ],
+        '=' => [
+            "     ", "     ", "#####", "     ", "#####", "     ", "     ",
+        ],
+        '/' => [
+            "     ", "    #", "   # ", "  #  ", " #   ", "#    ", "     ",
+        ],
+        '(' => [
+            "   # ", "  #  ", " #   ", " #   ", " #   ", "  #  ", "   # ",
+        ],
+        ')' => [
+            " #   ", "  #  ", "   # ", "   # ", "   # ", "  #  ", " #   ",
+        ],
+        '&' => [
+            " ##  ", "#  # ", "# #  ", " #   ", "# # #", "#  # ", " ## #",
+        ],
+        '#' => [
+            " # # ", " # # ", "#####", " # # ", "#####", " # # ", " # # ",
+        ],
+        '*' => [
+            "     ", "  #  ", "# # #", " ### ", "# # #", "  #  ", "     ",
+        ],
+        '<' => [
+            "   # ", "  #  ", " #   ", "#    ", " #   ", "  #  ", "   # ",
+        ],
+        '>' => [
+            " #   ", "  #  ", "   # ", "    #", "   # ", "  #  ", " #   ",
+        ],
+        '_' => [
+            "     ", "     ", "     ", "     ", "     ", "     ", "#####",
+        ],
+        _ => return None,
+    };
+    Some(g)
+}
+
+/// Render a text into a bitmap of lit pixels, indexed as `bitmap[row][column]`
+///
+/// # Arguments
+///
+/// * `text` - The text to render, `\n` starts a new line and unsupported characters are drawn as blank
+/// * `spacing` - Number of blank columns between two characters
+///
+/// # Notes
+///
+/// - All rows of the returned bitmap have the same length
+/// - Lines are separated by one blank row and are aligned to the center
+pub fn text_bitmap(text: &str, spacing: usize) -> Vec<Vec<bool>> {
+    let lines = text
+        .lines()
+        .map(|line| {
+            let mut rows = vec![Vec::new(); GLYPH_HEIGHT];
+            for (i, c) in line.chars().enumerate() {
+                let g = glyph(c).unwrap_or_else(|| glyph(' ').expect("Space glyph is defined."));
+                for (row, bits) in rows.iter_mut().zip(g.iter()) {
+                    if i > 0 {
+                        row.resize(row.len() + spacing, false);
+                    }
+                    row.extend(bits.chars().map(|b| b == '#'));
+                }
+            }
+            rows
+        })
+        .collect::<Vec<_>>();
+    let width = lines.iter().map(|rows| rows[0].len()).max().unwrap_or(0);
+    let mut bitmap = Vec::new();
+    for (i, rows) in lines.into_iter().enumerate() {
+        if i > 0 {
+            bitmap.push(vec![false; width]);
+        }
+        for row in rows {
+            let left = (width - row.len()) / 2;
+            let mut line = vec![false; left];
+            line.extend(row);
+            line.resize(width, false);
+            bitmap.push(line);
+        }
+    }
+    bitmap
+}
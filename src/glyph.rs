@@ -0,0 +1,333 @@
+//! `glyph` module provides the palettes of characters `Particle`s are drawn with
+//!
+//! A `GlyphPalette` can be set for the whole program with `Config::glyphs`, or for one `Firework` with `FireworkConfig::with_glyphs`
+
+use std::{borrow::Cow, fs, io, path::Path};
+
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::particle::LifeState;
+
+/// Characters drawn for densities below `max_density`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphBand {
+    pub max_density: f32,
+    pub glyphs: Cow<'static, str>,
+}
+
+/// Characters `Particle`s are drawn with, in density bands for every `LifeState`
+///
+/// # Notes
+///
+/// - Bands of a `LifeState` are sorted by `max_density`, densities above the last band use the last band
+/// - Double-width glyphs like emoji and kana take two columns when cjk is disabled
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphPalette {
+    pub alive: Cow<'static, [GlyphBand]>,
+    pub declining: Cow<'static, [GlyphBand]>,
+    pub dying: Cow<'static, [GlyphBand]>,
+    /// Characters of the sparkles behind glittering `Particle`s, see `Effect::Glitter`
+    pub sparkles: Cow<'static, str>,
+}
+
+const fn band(max_density: f32, glyphs: &'static str) -> GlyphBand {
+    GlyphBand {
+        max_density,
+        glyphs: Cow::Borrowed(glyphs),
+    }
+}
+
+impl GlyphPalette {
+    /// Ascii characters, the default palette
+    pub const ASCII: GlyphPalette = GlyphPalette {
+        alive: Cow::Borrowed(&[
+            band(0.3, "`'. "),
+            band(0.5, "/\\|()1{}[]?"),
+            band(0.7, "oahkbdpqwmZO0QLCJUYXzcvunxrjft*"),
+            band(1., "$@B%8&WM#"),
+        ]),
+        declining: Cow::Borrowed(&[
+            band(0.2, "` '. "),
+            band(0.6, "-_ +~<> i!lI;:,\"^"),
+            band(0.85, "/\\| ()1{}[ ]?"),
+            band(1., "xrjft*"),
+        ]),
+        dying: Cow::Borrowed(&[
+            band(0.6, ".  ,`.    ^,' . "),
+            band(1., " /\\| ( )  1{} [  ]?i !l I;: ,\"^ "),
+        ]),
+        sparkles: Cow::Borrowed("*+"),
+    };
+
+    /// Chinese characters, the palette when cjk is enabled
+    pub const CJK: GlyphPalette = GlyphPalette {
+        alive: Cow::Borrowed(&[
+            band(0.3, "。，”“』 『￥"),
+            band(0.5, "一二三二三五十十已于上下义天"),
+            band(0.7, "时中自字木月日目火田左右点以"),
+            band(1., "龖龠龜"),
+        ]),
+        declining: Cow::Borrowed(&[
+            band(0.2, "？。， 『』 ||"),
+            band(0.6, "（）【】*￥|十一二三六"),
+            band(0.85, "人中亿入上下火土"),
+            band(1., "繁荣昌盛国泰民安龍龖龠龜耋"),
+        ]),
+        dying: Cow::Borrowed(&[
+            band(0.6, "。 『 』 、： |。，— ……"),
+            band(1., "|￥人 上十入乙小 下"),
+        ]),
+        sparkles: Cow::Borrowed("＊＋"),
+    };
+
+    /// Japanese kana and kanji
+    pub const KANA: GlyphPalette = GlyphPalette {
+        alive: Cow::Borrowed(&[
+            band(0.3, "。，”“』 『￥"),
+            band(0.5, "いうよへくひとフーク "),
+            band(
+                0.7,
+                "探しているのが誰かなのかどこかなのかそれともただ単に就職先なのか自分でもよくわからない",
+            ),
+            band(1., "東京福岡横浜縄"),
+        ]),
+        declining: Cow::Borrowed(&[
+            band(0.2, "？。， 『』 ||"),
+            band(0.6, "（）【】*￥|ソファー"),
+            band(0.85, "人ならざるものに出会うかもしれない"),
+            band(1., "時間言葉目覚"),
+        ]),
+        dying: Cow::Borrowed(&[
+            band(0.6, "。 『 』 、： |。，— ……"),
+            band(1., "イントマトナイフ"),
+        ]),
+        sparkles: Cow::Borrowed("＊＋"),
+    };
+
+    /// Braille patterns, denser with more dots
+    pub const BRAILLE: GlyphPalette = GlyphPalette {
+        alive: Cow::Borrowed(&[
+            band(0.3, "⠁⠂⠄⠈⠐⠠"),
+            band(0.5, "⠃⠅⠉⠑⠡⠢⠤⠆"),
+            band(0.7, "⠇⠏⠗⠧⠹⠼⠾⡇"),
+            band(1., "⣿⣷⣾⣯⣟⡿⢿⣻⣽"),
+        ]),
+        declining: Cow::Borrowed(&[
+            band(0.2, "⠁⠂⠄⠈ "),
+            band(0.6, "⠃⠅⠉⠑⠆"),
+            band(0.85, "⠇⠏⠗⠧"),
+            band(1., "⠿⡷⢾"),
+        ]),
+        dying: Cow::Borrowed(&[band(0.6, "⠁ ⠂ ⠄ ⠈  "), band(1., "⠃⠅ ⠉ ⠑ ")]),
+        sparkles: Cow::Borrowed("⠂⠈⠐⠠"),
+    };
+
+    /// Box-drawing lines and blocks
+    pub const BOX: GlyphPalette = GlyphPalette {
+        alive: Cow::Borrowed(&[
+            band(0.3, "╴╵╶╷"),
+            band(0.5, "─│┌┐└┘"),
+            band(0.7, "├┤┬┴┼"),
+            band(1., "╋╬█▓"),
+        ]),
+        declining: Cow::Borrowed(&[
+            band(0.2, "╴╵╶╷ "),
+            band(0.6, "─│╌╎"),
+            band(0.85, "┌┐└┘"),
+            band(1., "▓▒"),
+        ]),
+        dying: Cow::Borrowed(&[band(0.6, "╴ ╵ ╶ ╷  "), band(1., "░ ╌ ╎ ")]),
+        sparkles: Cow::Borrowed("┼╋"),
+    };
+
+    /// Emoji sparkles, stars and bursts
+    pub const EMOJI: GlyphPalette = GlyphPalette {
+        alive: Cow::Borrowed(&[
+            band(0.3, "·✨"),
+            band(0.5, "✨⭐"),
+            band(0.7, "🌟💫"),
+            band(1., "🎆🎇💥"),
+        ]),
+        declining: Cow::Borrowed(&[band(0.2, "· "), band(0.6, "✨·"), band(1., "⭐✨")]),
+        dying: Cow::Borrowed(&[band(0.6, "·  . "), band(1., "· ✨ ")]),
+        sparkles: Cow::Borrowed("✨⭐"),
+    };
+
+    /// Names of the built-in palettes, see `named`
+    pub const NAMES: [&'static str; 6] = ["ascii", "cjk", "kana", "braille", "box", "emoji"];
+
+    /// Return the built-in palette `name`, ignoring case, or `None` if there is no such palette, see `NAMES`
+    pub fn named(name: &str) -> Option<Self> {
+        Some(match name.trim().to_ascii_lowercase().as_str() {
+            "ascii" => Self::ASCII,
+            "cjk" => Self::CJK,
+            "kana" => Self::KANA,
+            "braille" => Self::BRAILLE,
+            "box" => Self::BOX,
+            "emoji" => Self::EMOJI,
+            _ => return None,
+        })
+    }
+
+    /// Return the bands of `life_state`, `Dead` has none
+    pub fn bands(&self, life_state: LifeState) -> &[GlyphBand] {
+        match life_state {
+            LifeState::Alive => &self.alive,
+            LifeState::Declining => &self.declining,
+            LifeState::Dying => &self.dying,
+            LifeState::Dead => &[],
+        }
+    }
+
+    /// Pick a random character for a `Particle` in `life_state` at `density`, `Dead` and empty bands return `None`
+    ///
+    /// # Arguments
+    ///
+    /// * `density` - A float between 0 and 1, larger `density` picks denser characters
+    pub fn get_char(
+        &self,
+        life_state: LifeState,
+        density: f32,
+        rng: &mut impl Rng,
+    ) -> Option<char> {
+        let bands = self.bands(life_state);
+        bands
+            .iter()
+            .find(|b| density < b.max_density)
+            .or(bands.last())?
+            .glyphs
+            .chars()
+            .choose(rng)
+    }
+
+    /// Pick a random character of `sparkles`, `None` if there is none
+    pub fn get_sparkle(&self, rng: &mut impl Rng) -> Option<char> {
+        self.sparkles.chars().choose(rng)
+    }
+
+    /// Parse a palette from text, one band per line like `alive 0.5 /\|()`
+    ///
+    /// A line is a `LifeState` (`alive`, `declining` or `dying`), the `max_density` of the band and its glyphs, separated by
+    /// whitespace. Glyphs start right after the single whitespace character following the density, so they may include
+    /// spaces. Empty lines and comments starting with `#` are skipped
+    ///
+    /// An optional line like `sparkle *+` sets `sparkles`, which default to the densest `declining` band
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (mut alive, mut declining, mut dying) = (Vec::new(), Vec::new(), Vec::new());
+        let mut sparkles = None;
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let (name, rest) = split_field(line);
+            let bands = match name.to_ascii_lowercase().as_str() {
+                "alive" => &mut alive,
+                "declining" => &mut declining,
+                "dying" => &mut dying,
+                "sparkle" => {
+                    let glyphs = rest.to_string();
+                    if glyphs.is_empty() {
+                        return Err(format!("line {}: no sparkle glyph", i + 1));
+                    }
+                    sparkles = Some(glyphs);
+                    continue;
+                }
+                s => {
+                    return Err(format!(
+                    "line {}: invalid life state `{}`, expected `alive`, `declining`, `dying` or `sparkle`",
+                    i + 1,
+                    s
+                ))
+                }
+            };
+            let (density, glyphs) = split_field(rest);
+            let max_density = density
+                .parse::<f32>()
+                .ok()
+                .filter(|d| d.is_finite())
+                .ok_or_else(|| format!("line {}: invalid density", i + 1))?;
+            if glyphs.is_empty() {
+                return Err(format!("line {}: band has no glyph", i + 1));
+            }
+            bands.push(GlyphBand {
+                max_density,
+                glyphs: Cow::Owned(glyphs.to_string()),
+            });
+        }
+        let finish =
+            |name: &str, mut bands: Vec<GlyphBand>| -> Result<Cow<'static, [GlyphBand]>, String> {
+                if bands.is_empty() {
+                    return Err(format!("no band for `{}`", name));
+                }
+                bands.sort_by(|a, b| a.max_density.total_cmp(&b.max_density));
+                Ok(Cow::Owned(bands))
+            };
+        let alive = finish("alive", alive)?;
+        let declining = finish("declining", declining)?;
+        let sparkles = match sparkles {
+            Some(sparkles) => sparkles,
+            None => declining[declining.len() - 1].glyphs.to_string(),
+        };
+        Ok(Self {
+            alive,
+            declining,
+            dying: finish("dying", dying)?,
+            sparkles: Cow::Owned(sparkles),
+        })
+    }
+
+    /// Load a palette from a file, see `parse`
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl Default for GlyphPalette {
+    fn default() -> Self {
+        Self::ASCII
+    }
+}
+
+/// Split the first field of `line` after any leading whitespace, the rest starts after the single whitespace character
+/// ending the field
+fn split_field(line: &str) -> (&str, &str) {
+    let line = line.trim_start();
+    match line.char_indices().find(|(_, c)| c.is_whitespace()) {
+        Some((i, c)) => (&line[..i], &line[i + c.len_utf8()..]),
+        None => (line, ""),
+    }
+}
+
+/// Return the number of terminal columns `c` takes, 2 for wide characters like cjk and emoji
+///
+/// # Notes
+///
+/// - This covers the common wide ranges of Unicode, not every corner case
+pub fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x231A..=0x231B
+        | 0x23E9..=0x23EC
+        | 0x2614..=0x2615
+        | 0x26A1
+        | 0x26AA..=0x26AB
+        | 0x2705
+        | 0x2728
+        | 0x274C
+        | 0x2B1B..=0x2B1C
+        | 0x2B50
+        | 0x2E80..=0x303E
+        | 0x3041..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
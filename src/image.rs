@@ -0,0 +1,140 @@
+//! `image` module provides small images that bursts can paint, see `utils::gen_points_image`
+
+use std::{fs, io, path::Path};
+
+/// An RGBA image, with pixels stored row by row from the top left corner
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<(u8, u8, u8, u8)>,
+}
+
+impl Image {
+    /// Create a new `Image` from its pixels, row by row from the top left corner
+    ///
+    /// # Notes
+    ///
+    /// - Missing pixels are transparent, extra pixels are dropped
+    pub fn new(width: usize, height: usize, mut pixels: Vec<(u8, u8, u8, u8)>) -> Self {
+        pixels.resize(width * height, (0, 0, 0, 0));
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Return the pixel at column `x` and row `y`
+    pub fn pixel(&self, x: usize, y: usize) -> (u8, u8, u8, u8) {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Load an image file, PPM files are always supported and PNG files need the `png` feature
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes = fs::read(path)?;
+        match bytes.get(..2) {
+            Some(b"P3" | b"P6") => Self::from_ppm(&bytes),
+            #[cfg(feature = "png")]
+            _ if bytes.starts_with(b"\x89PNG") => Self::from_png(&bytes),
+            _ => Err(invalid_data("unsupported image format")),
+        }
+    }
+
+    /// Decode a PPM image, both ascii (`P3`) and binary (`P6`)
+    pub fn from_ppm(bytes: &[u8]) -> io::Result<Self> {
+        let binary = match bytes.get(..2) {
+            Some(b"P3") => false,
+            Some(b"P6") => true,
+            _ => return Err(invalid_data("not a PPM image")),
+        };
+        let mut pos = 2;
+        let mut header = [0; 3];
+        for value in header.iter_mut() {
+            *value = read_ppm_number(bytes, &mut pos)?;
+        }
+        let [width, height, max] = header;
+        if max == 0 || max > 65535 {
+            return Err(invalid_data("invalid PPM max value"));
+        }
+        let scale = |v: usize| (v.min(max) * 255 / max) as u8;
+        let n = width
+            .checked_mul(height)
+            .filter(|n| *n <= bytes.len())
+            .ok_or_else(|| invalid_data("truncated PPM image"))?;
+        let mut pixels = Vec::with_capacity(n);
+        if binary {
+            // A single whitespace separates the header from the raster
+            pos += 1;
+            let size = if max < 256 { 1 } else { 2 };
+            let raster = bytes
+                .get(pos..pos + n * 3 * size)
+                .ok_or_else(|| invalid_data("truncated PPM image"))?;
+            let values = raster
+                .chunks(size)
+                .map(|c| c.iter().fold(0, |acc, b| acc << 8 | *b as usize))
+                .collect::<Vec<_>>();
+            pixels.extend(
+                values
+                    .chunks(3)
+                    .map(|c| (scale(c[0]), scale(c[1]), scale(c[2]), 255)),
+            );
+        } else {
+            for _ in 0..n {
+                let r = read_ppm_number(bytes, &mut pos)?;
+                let g = read_ppm_number(bytes, &mut pos)?;
+                let b = read_ppm_number(bytes, &mut pos)?;
+                pixels.push((scale(r), scale(g), scale(b), 255));
+            }
+        }
+        Ok(Self::new(width, height, pixels))
+    }
+
+    /// Decode a PNG image of any color type and bit depth
+    #[cfg(feature = "png")]
+    pub fn from_png(bytes: &[u8]) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(invalid_data)?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(invalid_data)?;
+        let buf = &buf[..info.buffer_size()];
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buf.chunks(4).map(|c| (c[0], c[1], c[2], c[3])).collect(),
+            png::ColorType::Rgb => buf.chunks(3).map(|c| (c[0], c[1], c[2], 255)).collect(),
+            png::ColorType::GrayscaleAlpha => {
+                buf.chunks(2).map(|c| (c[0], c[0], c[0], c[1])).collect()
+            }
+            png::ColorType::Grayscale => buf.iter().map(|v| (*v, *v, *v, 255)).collect(),
+            png::ColorType::Indexed => return Err(invalid_data("unexpanded indexed PNG image")),
+        };
+        Ok(Self::new(info.width as usize, info.height as usize, pixels))
+    }
+}
+
+/// Read the next number of a PPM header or ascii raster, skipping whitespace and comments
+fn read_ppm_number(bytes: &[u8], pos: &mut usize) -> io::Result<usize> {
+    loop {
+        match bytes.get(*pos) {
+            Some(b'#') => {
+                while !matches!(bytes.get(*pos), Some(b'\n') | None) {
+                    *pos += 1;
+                }
+            }
+            Some(c) if c.is_ascii_whitespace() => *pos += 1,
+            _ => break,
+        }
+    }
+    let st = *pos;
+    while bytes.get(*pos).is_some_and(|c| c.is_ascii_digit()) {
+        *pos += 1;
+    }
+    std::str::from_utf8(&bytes[st..*pos])
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| invalid_data("invalid PPM number"))
+}
+
+fn invalid_data(error: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
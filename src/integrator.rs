@@ -0,0 +1,61 @@
+//! `integrator` module provides numerical integrators used to move particles forward in time
+
+use std::ops::{Add, AddAssign, Div, Mul};
+
+/// Numerical method used to advance a `Particle` by one simulation step
+///
+/// All the integrators take an acceleration function of position and velocity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Integrator {
+    /// Update velocity first, then position with the new velocity
+    ///
+    /// Cheap and stable enough for small time steps
+    #[default]
+    SemiImplicitEuler,
+    /// Velocity Verlet, second order accurate
+    Verlet,
+    /// Classic fourth order Runge-Kutta, the most accurate and the most expensive
+    Rk4,
+}
+
+impl Integrator {
+    /// Advance `pos` and `vel` by `dt` seconds
+    ///
+    /// Works with `Vec2` for flat `Particle`s and `Vec3` for `Particle`s moving in depth
+    ///
+    /// # Arguments
+    ///
+    /// * `acc` - Acceleration at a given position and velocity
+    pub fn step<V>(&self, pos: &mut V, vel: &mut V, dt: f32, acc: impl Fn(V, V) -> V)
+    where
+        V: Copy + Add<Output = V> + AddAssign + Mul<f32, Output = V> + Div<f32, Output = V>,
+        f32: Mul<V, Output = V>,
+    {
+        match self {
+            Integrator::SemiImplicitEuler => {
+                *vel += acc(*pos, *vel) * dt;
+                *pos += *vel * dt;
+            }
+            Integrator::Verlet => {
+                let a0 = acc(*pos, *vel);
+                *pos += *vel * dt + 0.5 * a0 * dt * dt;
+                // Velocity-dependent forces are evaluated with a predicted velocity
+                let a1 = acc(*pos, *vel + a0 * dt);
+                *vel += 0.5 * (a0 + a1) * dt;
+            }
+            Integrator::Rk4 => {
+                let (p0, v0) = (*pos, *vel);
+                let k1v = acc(p0, v0);
+                let k1p = v0;
+                let k2v = acc(p0 + k1p * dt / 2., v0 + k1v * dt / 2.);
+                let k2p = v0 + k1v * dt / 2.;
+                let k3v = acc(p0 + k2p * dt / 2., v0 + k2v * dt / 2.);
+                let k3p = v0 + k2v * dt / 2.;
+                let k4v = acc(p0 + k3p * dt, v0 + k3v * dt);
+                let k4p = v0 + k3v * dt;
+                *pos = p0 + (k1p + 2. * k2p + 2. * k3p + k4p) * dt / 6.;
+                *vel = v0 + (k1v + 2. * k2v + 2. * k3v + k4v) * dt / 6.;
+            }
+        }
+    }
+}
@@ -1,6 +1,20 @@
+pub mod boundary;
+pub mod camera;
+pub mod chemistry;
 pub mod config;
 pub mod demo;
+pub mod field;
 pub mod fireworks;
+pub mod font;
+pub mod glyph;
+pub mod image;
+pub mod integrator;
+pub mod palette;
 pub mod particle;
+pub mod scene;
+pub mod shell;
+pub mod smoke;
+pub mod store;
 pub mod term;
+pub mod text;
 pub mod utils;
@@ -0,0 +1,465 @@
+//! `palette` module provides named color palettes, generated color harmonies and palettes loaded from files
+
+use std::{f32::consts::PI, fs, io, path::Path};
+
+use rand::{seq::SliceRandom, Rng};
+
+/// Warm reds and yellows
+pub const EMBER: &[(u8, u8, u8)] = &[
+    (255, 102, 75),
+    (144, 56, 67),
+    (255, 225, 124),
+    (206, 32, 41),
+];
+/// Bright pink, yellow, red, blue and orange
+pub const CARNIVAL: &[(u8, u8, u8)] = &[
+    (235, 39, 155),
+    (250, 216, 68),
+    (242, 52, 72),
+    (63, 52, 200),
+    (255, 139, 57),
+];
+/// Icy blues fading to white
+pub const GLACIER: &[(u8, u8, u8)] = &[
+    (152, 186, 227),
+    (89, 129, 177),
+    (54, 84, 117),
+    (240, 244, 254),
+];
+/// Deep blue to light green
+pub const LAGOON: &[(u8, u8, u8)] = &[
+    (34, 87, 122),
+    (56, 163, 165),
+    (87, 204, 153),
+    (128, 237, 153),
+    (199, 249, 204),
+];
+/// Soft lilac, pink and sky blue
+pub const PASTEL: &[(u8, u8, u8)] = &[
+    (205, 180, 219),
+    (255, 200, 221),
+    (255, 175, 204),
+    (189, 224, 254),
+    (162, 210, 255),
+];
+/// Dark wine reds to orange
+pub const WINE: &[(u8, u8, u8)] = &[
+    (79, 0, 11),
+    (114, 0, 38),
+    (206, 66, 87),
+    (255, 127, 81),
+    (255, 155, 84),
+];
+/// Navy blue and gold
+pub const ROYAL: &[(u8, u8, u8)] = &[(0, 29, 61), (0, 53, 102), (255, 195, 0), (255, 214, 10)];
+/// Violet and orange
+pub const DUSK: &[(u8, u8, u8)] = &[
+    (61, 52, 139),
+    (118, 120, 237),
+    (247, 184, 1),
+    (241, 135, 1),
+    (243, 91, 4),
+];
+/// Pale gold and white
+pub const CHAMPAGNE: &[(u8, u8, u8)] = &[(242, 233, 190), (226, 196, 136), (255, 248, 253)];
+/// Blues of the night sky
+pub const MIDNIGHT: &[(u8, u8, u8)] = &[(152, 186, 227), (54, 84, 117), (21, 39, 60)];
+/// Amber, orange and white
+pub const AMBER: &[(u8, u8, u8)] = &[(255, 183, 3), (251, 133, 0), (255, 245, 253)];
+/// Cyan and light blue
+pub const OCEAN: &[(u8, u8, u8)] = &[(72, 202, 228), (0, 150, 199), (202, 240, 248)];
+/// Teal, mint and white
+pub const TEAL: &[(u8, u8, u8)] = &[(17, 138, 178), (6, 214, 160), (7, 59, 76), (255, 255, 255)];
+/// Warm white
+pub const SILVER: &[(u8, u8, u8)] = &[(255, 216, 190), (255, 238, 221), (248, 247, 255)];
+/// All the colors of the rainbow
+pub const RAINBOW: &[(u8, u8, u8)] = &[
+    (255, 59, 48),
+    (255, 149, 0),
+    (255, 221, 0),
+    (52, 199, 89),
+    (0, 170, 255),
+    (88, 86, 214),
+    (175, 82, 222),
+];
+/// Glowing magenta, cyan and lime
+pub const NEON: &[(u8, u8, u8)] = &[(255, 0, 153), (0, 255, 255), (57, 255, 20), (255, 240, 31)];
+/// Red, green and gold
+pub const CHRISTMAS: &[(u8, u8, u8)] =
+    &[(214, 40, 40), (0, 135, 62), (247, 127, 0), (252, 191, 73)];
+
+/// Single gold
+pub const GOLD: &[(u8, u8, u8)] = &[(250, 216, 68)];
+/// Cream, gold, sage and navy
+pub const HARBOR: &[(u8, u8, u8)] = &[
+    (242, 233, 190),
+    (226, 196, 136),
+    (149, 202, 176),
+    (26, 64, 126),
+];
+/// Marigold, orange and cream
+pub const MARIGOLD: &[(u8, u8, u8)] = &[(255, 183, 3), (251, 133, 0), (242, 233, 190)];
+/// Gold, white and raspberry
+pub const ROSE: &[(u8, u8, u8)] = &[(226, 196, 136), (255, 245, 253), (208, 58, 99)];
+/// Blue, sky blue and cyan
+pub const AZURE: &[(u8, u8, u8)] = &[(0, 119, 182), (144, 224, 239), (12, 180, 216)];
+/// Magenta, pink and purple
+pub const ORCHID: &[(u8, u8, u8)] = &[(181, 23, 158), (247, 37, 133), (114, 9, 183)];
+/// Lime and sea greens
+pub const MEADOW: &[(u8, u8, u8)] = &[(217, 237, 146), (153, 217, 140), (82, 182, 154)];
+
+/// Name and colors of a palette
+type NamedColors = (&'static str, &'static [(u8, u8, u8)]);
+
+/// All the named palettes
+pub const NAMED: [NamedColors; 24] = [
+    ("ember", EMBER),
+    ("carnival", CARNIVAL),
+    ("glacier", GLACIER),
+    ("lagoon", LAGOON),
+    ("pastel", PASTEL),
+    ("wine", WINE),
+    ("royal", ROYAL),
+    ("dusk", DUSK),
+    ("champagne", CHAMPAGNE),
+    ("midnight", MIDNIGHT),
+    ("amber", AMBER),
+    ("ocean", OCEAN),
+    ("teal", TEAL),
+    ("silver", SILVER),
+    ("rainbow", RAINBOW),
+    ("neon", NEON),
+    ("christmas", CHRISTMAS),
+    ("gold", GOLD),
+    ("harbor", HARBOR),
+    ("marigold", MARIGOLD),
+    ("rose", ROSE),
+    ("azure", AZURE),
+    ("orchid", ORCHID),
+    ("meadow", MEADOW),
+];
+
+/// A named list of colors
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Palette {
+    pub name: String,
+    pub colors: Vec<(u8, u8, u8)>,
+}
+
+/// Color harmonies generated from a base color by rotating its hue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Harmony {
+    /// The base color and the opposite hue
+    Complementary,
+    /// Three hues evenly spaced around the color wheel
+    Triadic,
+    /// The base color and its neighbouring hues
+    Analogous,
+}
+
+/// Color space in which the hue of a `Harmony` is rotated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// Hue, saturation and lightness, the classic color wheel
+    #[default]
+    Hsl,
+    /// Perceptual lightness, chroma and hue, the generated colors look equally bright
+    Oklch,
+}
+
+impl Harmony {
+    /// All the `Harmony`s
+    pub const ALL: [Harmony; 3] = [Harmony::Complementary, Harmony::Triadic, Harmony::Analogous];
+
+    /// Return the lowercase name of the `Harmony`, e.g. `"triadic"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Harmony::Complementary => "complementary",
+            Harmony::Triadic => "triadic",
+            Harmony::Analogous => "analogous",
+        }
+    }
+
+    /// Return the `Harmony` named `name`, ignoring case, or `None` if there is no such harmony
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|h| h.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Return the hue offsets in degrees of the colors of the `Harmony`
+    fn hue_offsets(&self) -> &'static [f32] {
+        match self {
+            Harmony::Complementary => &[0., 180.],
+            Harmony::Triadic => &[0., 120., 240.],
+            Harmony::Analogous => &[-30., 0., 30.],
+        }
+    }
+}
+
+impl Palette {
+    /// Create a new `Palette`
+    pub fn new(name: impl Into<String>, colors: Vec<(u8, u8, u8)>) -> Self {
+        Self {
+            name: name.into(),
+            colors,
+        }
+    }
+
+    /// Return the named palette `name`, ignoring case, or `None` if there is no such palette, see `NAMED`
+    pub fn named(name: &str) -> Option<Self> {
+        NAMED
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+            .map(|(n, c)| Self::new(*n, c.to_vec()))
+    }
+
+    /// Generate a `Palette` of `harmony` from `base`
+    ///
+    /// Every hue comes with a lighter tint, so the `Palette` has twice as many colors as the hues of `harmony`
+    pub fn harmony(base: (u8, u8, u8), harmony: Harmony, space: ColorSpace) -> Self {
+        let mut colors = Vec::new();
+        for offset in harmony.hue_offsets() {
+            let (color, tint) = match space {
+                ColorSpace::Hsl => {
+                    let (h, s, l) = rgb_to_hsl(base);
+                    let h = (h + offset).rem_euclid(360.);
+                    (
+                        hsl_to_rgb((h, s, l)),
+                        hsl_to_rgb((h, s, l + (1. - l) * 0.5)),
+                    )
+                }
+                ColorSpace::Oklch => {
+                    let (l, c, h) = rgb_to_oklch(base);
+                    let h = (h + offset).rem_euclid(360.);
+                    (
+                        oklch_to_rgb((l, c, h)),
+                        oklch_to_rgb((l + (1. - l) * 0.5, c * 0.6, h)),
+                    )
+                }
+            };
+            colors.push(color);
+            colors.push(tint);
+        }
+        Self::new(
+            format!(
+                "{}-{}",
+                harmony.name(),
+                to_hex(base).trim_start_matches('#')
+            ),
+            colors,
+        )
+    }
+
+    /// Parse palettes from text, one palette per line like `sunset: #ff5f6d #ffc371 255,255,255`
+    ///
+    /// Empty lines and comments starting with `# ` are skipped. A JSON object is also accepted with the `json` feature,
+    /// see `from_json`
+    pub fn parse(text: &str) -> Result<Vec<Self>, String> {
+        if text.trim_start().starts_with('{') {
+            #[cfg(feature = "json")]
+            return Self::from_json(text);
+            #[cfg(not(feature = "json"))]
+            return Err("JSON palettes need the `json` feature".to_string());
+        }
+        let mut res = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line == "#" || line.starts_with("# ") {
+                continue;
+            }
+            let (name, colors) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {}: expected `name: colors`", i + 1))?;
+            let colors = colors
+                .split_whitespace()
+                .map(|c| {
+                    parse_color(c).ok_or_else(|| format!("line {}: invalid color `{}`", i + 1, c))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if colors.is_empty() {
+                return Err(format!(
+                    "line {}: palette `{}` has no color",
+                    i + 1,
+                    name.trim()
+                ));
+            }
+            res.push(Self::new(name.trim(), colors));
+        }
+        Ok(res)
+    }
+
+    /// Parse palettes from a JSON object mapping names to colors, like `{"sunset": ["#ff5f6d", [255, 195, 113]]}`
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Vec<Self>, String> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| "expected an object mapping names to colors".to_string())?;
+        object
+            .iter()
+            .map(|(name, colors)| {
+                let colors = colors
+                    .as_array()
+                    .filter(|c| !c.is_empty())
+                    .ok_or_else(|| format!("palette `{}` is not a list of colors", name))?
+                    .iter()
+                    .map(|c| {
+                        json_color(c).ok_or_else(|| format!("invalid color `{}` in `{}`", c, name))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Self::new(name.as_str(), colors))
+            })
+            .collect()
+    }
+
+    /// Load palettes from a text file, or a JSON file with the `json` feature, see `parse`
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        Self::parse(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Return a random color of the `Palette`, or white if it is empty
+    pub fn choose(&self, rng: &mut impl Rng) -> (u8, u8, u8) {
+        *self.colors.choose(rng).unwrap_or(&(255, 255, 255))
+    }
+}
+
+/// Parse a color like `#ffd60a` or `255,214,10`
+pub fn parse_color(s: &str) -> Option<(u8, u8, u8)> {
+    let s = s.trim();
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let v = u32::from_str_radix(hex, 16).ok()?;
+        return Some(((v >> 16) as u8, (v >> 8) as u8, v as u8));
+    }
+    let c = s
+        .split(',')
+        .map(|v| v.trim().parse::<u8>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match c[..] {
+        [r, g, b] => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+/// Return a color as a hex string like `#ffd60a`
+pub fn to_hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(feature = "json")]
+fn json_color(value: &serde_json::Value) -> Option<(u8, u8, u8)> {
+    match value {
+        serde_json::Value::String(s) => parse_color(s),
+        serde_json::Value::Array(c) => {
+            let c = c
+                .iter()
+                .map(|v| v.as_u64().and_then(|v| u8::try_from(v).ok()))
+                .collect::<Option<Vec<_>>>()?;
+            match c[..] {
+                [r, g, b] => Some((r, g, b)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Convert a color to hue in degrees, saturation and lightness between 0 and 1
+pub fn rgb_to_hsl((r, g, b): (u8, u8, u8)) -> (f32, f32, f32) {
+    let (r, g, b) = (r as f32 / 255., g as f32 / 255., b as f32 / 255.);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.;
+    let d = max - min;
+    if d == 0. {
+        return (0., 0., l);
+    }
+    let s = d / (1. - (2. * l - 1.).abs());
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.)
+    } else if max == g {
+        (b - r) / d + 2.
+    } else {
+        (r - g) / d + 4.
+    };
+    (h * 60., s, l)
+}
+
+/// Convert hue in degrees, saturation and lightness between 0 and 1 to a color
+pub fn hsl_to_rgb((h, s, l): (f32, f32, f32)) -> (u8, u8, u8) {
+    let c = (1. - (2. * l - 1.).abs()) * s;
+    let h = h.rem_euclid(360.) / 60.;
+    let x = c * (1. - (h.rem_euclid(2.) - 1.).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (c, x, 0.),
+        1 => (x, c, 0.),
+        2 => (0., c, x),
+        3 => (0., x, c),
+        4 => (x, 0., c),
+        _ => (c, 0., x),
+    };
+    let m = l - c / 2.;
+    let to_u8 = |v: f32| ((v + m).clamp(0., 1.) * 255.).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// Convert a color to OKLCH, lightness between 0 and 1, chroma, and hue in degrees
+pub fn rgb_to_oklch(color: (u8, u8, u8)) -> (f32, f32, f32) {
+    let lin = |v: u8| {
+        let v = v as f32 / 255.;
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (lin(color.0), lin(color.1), lin(color.2));
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    let ok_l = 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s;
+    let ok_a = 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s;
+    let ok_b = 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s;
+    (
+        ok_l,
+        ok_a.hypot(ok_b),
+        ok_b.atan2(ok_a).to_degrees().rem_euclid(360.),
+    )
+}
+
+/// Convert OKLCH, lightness between 0 and 1, chroma, and hue in degrees, to a color
+///
+/// Colors out of the sRGB gamut lose chroma until they fit
+pub fn oklch_to_rgb((l, c, h): (f32, f32, f32)) -> (u8, u8, u8) {
+    let to_linear = |c: f32| {
+        let (a, b) = (c * (h * PI / 180.).cos(), c * (h * PI / 180.).sin());
+        let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+        [
+            4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_,
+            -1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_,
+            -0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_,
+        ]
+    };
+    let mut c = c;
+    let mut rgb = to_linear(c);
+    while c > 1e-4 && rgb.iter().any(|v| !(-1e-4..=1.0001).contains(v)) {
+        c *= 0.95;
+        rgb = to_linear(c);
+    }
+    let gamma = |v: f32| {
+        let v = v.clamp(0., 1.);
+        let v = if v <= 0.003_130_8 {
+            v * 12.92
+        } else {
+            1.055 * v.powf(1. / 2.4) - 0.055
+        };
+        (v * 255.).round() as u8
+    };
+    (gamma(rgb[0]), gamma(rgb[1]), gamma(rgb[2]))
+}
@@ -1,10 +1,14 @@
 //! `particle` module provides functions to define, create and update particles
 
-use std::{collections::VecDeque, time::Duration};
+use std::time::Duration;
 
-use glam::Vec2;
+use glam::{Vec2, Vec3};
+use rand::{Rng, RngCore};
 
-use crate::fireworks::FireworkConfig;
+use crate::{chemistry::Emission, fireworks::FireworkConfig};
+
+/// Free values kept with every `Particle` for `additional_force`, all zero when the `Particle` spawns
+pub type UserData = [f32; 4];
 
 /// The struct represents the states in a `Particle`'s lifetime
 ///
@@ -18,12 +22,16 @@ pub enum LifeState {
 }
 
 /// The struct representing a single particle
-#[derive(Debug, Clone)]
+///
+/// `Firework`s keep their `Particle`s in a `ParticleStore`, which also records their trails
+#[derive(Debug, Clone, Copy)]
 pub struct Particle {
     pub pos: Vec2,
     pub vel: Vec2,
-    /// Records a `trail_length` of previous positions of the `Particle`
-    pub trail: VecDeque<Vec2>,
+    /// Depth of the `Particle`, `z` axis points away from the viewer
+    pub z: f32,
+    /// Velocity along the `z` axis, a `Particle` with zero `vel_z` moves in its plane only
+    pub vel_z: f32,
     pub life_state: LifeState,
     /// `Duration` since initialization of this `Particle`
     pub time_elapsed: Duration,
@@ -35,7 +43,8 @@ impl Default for Particle {
         Self {
             pos: Vec2::ZERO,
             vel: Vec2::ZERO,
-            trail: VecDeque::new(),
+            z: 0.,
+            vel_z: 0.,
             life_state: LifeState::Alive,
             time_elapsed: Duration::ZERO,
             config: ParticleConfig::default(),
@@ -52,12 +61,12 @@ impl Particle {
         life_time: Duration,
         color: (u8, u8, u8),
     ) -> Self {
-        let trail = VecDeque::from(vec![pos; trail_length]);
         let life_state = LifeState::Alive;
         Self {
             pos,
             vel,
-            trail,
+            z: 0.,
+            vel_z: 0.,
             life_state,
             time_elapsed: Duration::ZERO,
             config: ParticleConfig::new(pos, vel, trail_length, life_time, color),
@@ -73,34 +82,185 @@ impl Particle {
     pub fn reset(&mut self) {
         self.pos = self.config.init_pos;
         self.vel = self.config.init_vel;
-        (0..self.config.trail_length).for_each(|i| self.trail[i] = self.pos);
+        self.z = self.config.init_z;
+        self.vel_z = self.config.init_vel_z;
         self.life_state = LifeState::Alive;
         self.time_elapsed = Duration::ZERO;
     }
 
-    /// Update the `Particle` based on delta time
+    /// Advance the `Particle` by one simulation step
+    ///
+    /// `additional_force` is evaluated once per step, at the state of the beginning of the step
     ///
     /// # Arguments
     ///
-    /// * - `duration` - `Duration` since last update
-    pub fn update(&mut self, duration: Duration, config: &FireworkConfig) {
-        const TIME_STEP: f32 = 0.001;
-        self.time_elapsed += duration;
-        self.life_state = cal_life_state(self.config.life_time, self.time_elapsed);
-        let mut t = 0.;
-        while t < duration.as_secs_f32() {
-            self.vel += TIME_STEP
-                * (Vec2::Y * 10. * config.gravity_scale
-                    - self.vel.normalize() * self.vel.length().powi(2) * config.ar_scale
-                    + (config.additional_force)(self));
-            self.pos += TIME_STEP * self.vel;
-            t += TIME_STEP;
+    /// * - `dt` - `Duration` of the simulation step
+    ///
+    /// # Notes
+    ///
+    /// - A `Particle` stepped alone has no `Firework`, so its `ForceContext` has `init_pos` as `center`,
+    ///   `time_elapsed` as `time` and `firework_age`, index 0 and zero `user_data` at every step
+    /// - `Effect::Crackle` needs a `ParticleStore` to spawn its sparks, a `Particle` stepped alone doesn't pop
+    pub fn step(&mut self, dt: Duration, config: &FireworkConfig) {
+        self.time_elapsed += dt;
+        self.life_state = cal_life_state(
+            self.config.life_time,
+            self.time_elapsed,
+            &config.life_curve_of(&self.config),
+        );
+        let additional = match &config.additional_force {
+            Some(f) => f(&mut ForceContext {
+                particle: self,
+                time: self.time_elapsed,
+                dt,
+                center: self.config.init_pos,
+                firework_age: self.time_elapsed,
+                index: 0,
+                user_data: &mut UserData::default(),
+                rng: ParticleRng::new(self, 0, self.time_elapsed),
+            }),
+            None => Vec2::ZERO,
+        };
+        advance(
+            &mut self.pos,
+            &mut self.vel,
+            &mut self.z,
+            &mut self.vel_z,
+            dt.as_secs_f32(),
+            additional,
+            config,
+        );
+    }
+
+    /// Return `time_elapsed` divided by `life_time`, from 0 when spawned to 1 when `Dead`
+    pub fn life_fraction(&self) -> f32 {
+        (self.time_elapsed.as_secs_f32() / self.config.life_time.as_secs_f32()).clamp(0., 1.)
+    }
+}
+
+/// Everything `additional_force` knows about the `Particle` it pushes, for one simulation step
+pub struct ForceContext<'a> {
+    pub particle: &'a Particle,
+    /// Simulation time of the `FireworkManager` at the beginning of the step
+    pub time: Duration,
+    /// `Duration` of the step
+    pub dt: Duration,
+    /// Center of the `Firework`
+    pub center: Vec2,
+    /// `Duration` since the `Firework` spawned, at the beginning of the step
+    pub firework_age: Duration,
+    /// Index of the `Particle` in the `ParticleStore` of its `Firework`
+    pub index: usize,
+    /// Values kept with the `Particle` from one step to the next, free to change
+    pub user_data: &'a mut UserData,
+    /// Random generator of this `Particle` and step
+    ///
+    /// It is seeded from the `Particle` and the simulation time, so results don't depend on threads
+    pub rng: ParticleRng,
+}
+
+impl ForceContext<'_> {
+    /// Return `time_elapsed` of the `Particle` divided by its `life_time`, from 0 to 1
+    pub fn life_fraction(&self) -> f32 {
+        self.particle.life_fraction()
+    }
+}
+
+/// Small and fast random generator given to `additional_force`, not suitable for cryptography
+///
+/// Its seed is only mixed when the first number is drawn, so a `ParticleRng` that is never used costs nothing
+#[derive(Debug, Clone)]
+pub struct ParticleRng {
+    state: u64,
+    /// Inputs of the seed which are not mixed into `state` yet
+    seed: Option<[u64; 7]>,
+}
+
+impl ParticleRng {
+    /// Create a `ParticleRng` for `particle` in slot `index` at simulation time `time`
+    pub fn new(particle: &Particle, index: usize, time: Duration) -> Self {
+        Self {
+            state: 0,
+            seed: Some([
+                index as u64,
+                time.as_nanos() as u64,
+                particle.time_elapsed.as_nanos() as u64,
+                particle.config.init_vel.x.to_bits() as u64,
+                particle.config.init_vel.y.to_bits() as u64,
+                particle.config.init_pos.x.to_bits() as u64,
+                particle.config.init_pos.y.to_bits() as u64,
+            ]),
+        }
+    }
+}
+
+/// Finalizer of SplitMix64, which spreads every bit of `z` over the whole result
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl RngCore for ParticleRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// SplitMix64
+    fn next_u64(&mut self) -> u64 {
+        if let Some(seed) = self.seed.take() {
+            // Every input is mixed before the next one, so different inputs don't cancel out
+            for v in seed {
+                self.state = mix(self.state.wrapping_add(0x9e37_79b9_7f4a_7c15) ^ v);
+            }
         }
-        self.trail.pop_front();
-        self.trail.push_back(self.pos);
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        mix(self.state)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let v = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&v[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
     }
 }
 
+/// Move a particle forward by `dt` seconds under gravity, air resistance and `additional` force
+///
+/// A particle with zero `vel_z` moves in its plane, otherwise it moves in 3D and air resistance depends on its speed in depth too
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn advance(
+    pos: &mut Vec2,
+    vel: &mut Vec2,
+    z: &mut f32,
+    vel_z: &mut f32,
+    dt: f32,
+    additional: Vec2,
+    config: &FireworkConfig,
+) {
+    if *vel_z == 0. {
+        let gravity = Vec2::Y * 10. * config.gravity_scale;
+        config.integrator.step(pos, vel, dt, |_, v| {
+            gravity - v * v.length() * config.ar_scale + additional
+        });
+        return;
+    }
+    let gravity = Vec3::Y * 10. * config.gravity_scale;
+    let additional = additional.extend(0.);
+    let (mut p, mut v) = (pos.extend(*z), vel.extend(*vel_z));
+    config.integrator.step(&mut p, &mut v, dt, |_, v| {
+        gravity - v * v.length() * config.ar_scale + additional
+    });
+    (*pos, *z) = (p.truncate(), p.z);
+    (*vel, *vel_z) = (v.truncate(), v.z);
+}
+
 /// Struct that defines the configuration of `Particle`
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct ParticleConfig {
@@ -111,6 +271,59 @@ pub struct ParticleConfig {
     pub life_time: Duration,
     /// Color in RGB (from 0 to 255)
     pub color: (u8, u8, u8),
+    /// How the color changes over the `Particle`'s life, see `Emission`
+    pub emission: Emission,
+    /// How the `Particle` flickers or pops, see `Effect`
+    pub effect: Effect,
+    /// Initial depth, see `Particle::z`
+    pub init_z: f32,
+    /// Initial velocity along the `z` axis, zero keeps the `Particle` flat
+    pub init_vel_z: f32,
+    /// How the `Particle` ages, `None` uses `FireworkConfig::life_curve`
+    pub life_curve: Option<LifeCurve>,
+}
+
+/// Special behavior of a `Particle`, set by `ParticleConfig::with_effect`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Effect {
+    /// Plain `Particle`
+    #[default]
+    None,
+    /// `Particle` blinks on and off `frequency` times per second
+    Strobe { frequency: f32 },
+    /// Trail of the `Particle` is drawn as random sparkles of the `GlyphPalette`, `density` from 0 to 1 is the part of the
+    /// trail that sparkles
+    Glitter { density: f32 },
+    /// `Particle` pops into `sparks` short-lived sparks flying at `speed` near the end of its life, see `Effect::CRACKLE_AT`
+    Crackle { sparks: usize, speed: f32 },
+}
+
+impl Effect {
+    /// Fraction of its `life_time` at which a crackling `Particle` pops
+    pub const CRACKLE_AT: f32 = 0.9;
+
+    /// Return true if the `Particle` at `index` of its `ParticleStore` is shown after `time_elapsed`
+    ///
+    /// Only strobing particles hide, every one of them blinks with its own phase
+    pub fn is_visible(&self, index: usize, time_elapsed: Duration) -> bool {
+        match self {
+            Effect::Strobe { frequency } => {
+                let phase = (index as f32 * 0.618_034).fract();
+                (time_elapsed.as_secs_f32() * frequency + phase).fract() < 0.5
+            }
+            _ => true,
+        }
+    }
+
+    /// Return true if a cell of the trail behind the head of a `Particle` sparkles
+    ///
+    /// A glittering trail has a sparkle on about half of `density` of its cells, other trails have none
+    pub fn sparkles(&self, rng: &mut impl Rng) -> bool {
+        match self {
+            Effect::Glitter { density } => rng.gen_bool(density.clamp(0., 1.) as f64 * 0.5),
+            _ => false,
+        }
+    }
 }
 
 impl Default for ParticleConfig {
@@ -121,6 +334,11 @@ impl Default for ParticleConfig {
             trail_length: 2,
             life_time: Duration::from_secs(3),
             color: (255, 255, 255),
+            emission: Emission::Fixed,
+            effect: Effect::None,
+            init_z: 0.,
+            init_vel_z: 0.,
+            life_curve: None,
         }
     }
 }
@@ -140,19 +358,177 @@ impl ParticleConfig {
             trail_length,
             life_time,
             color,
+            emission: Emission::Fixed,
+            effect: Effect::None,
+            init_z: 0.,
+            init_vel_z: 0.,
+            life_curve: None,
         }
     }
+
+    /// Set `init_z` and `init_vel_z`
+    #[inline]
+    #[must_use]
+    pub fn with_depth(mut self, z: f32, vel_z: f32) -> Self {
+        self.init_z = z;
+        self.init_vel_z = vel_z;
+        self
+    }
+
+    /// Set `effect`
+    #[inline]
+    #[must_use]
+    pub fn with_effect(mut self, effect: Effect) -> Self {
+        self.effect = effect;
+        self
+    }
+
+    /// Set `emission`, `color` is kept as the base color of `Emission::color`, see `color_at`
+    #[inline]
+    #[must_use]
+    pub fn with_emission(mut self, emission: Emission) -> Self {
+        self.emission = emission;
+        self
+    }
+
+    /// Set `life_curve`, overriding `FireworkConfig::life_curve` for this `Particle`
+    #[inline]
+    #[must_use]
+    pub fn with_life_curve(mut self, life_curve: LifeCurve) -> Self {
+        self.life_curve = Some(life_curve);
+        self
+    }
+
+    /// Return the color at `life_fraction` of the `Particle`'s life, from 0 to 1, see `Emission`
+    pub fn color_at(&self, life_fraction: f32) -> (u8, u8, u8) {
+        self.emission.color(self.color, life_fraction)
+    }
 }
 
-fn cal_life_state(life_time: Duration, current_elapsed: Duration) -> LifeState {
-    let p = current_elapsed.as_millis() as f32 / life_time.as_millis() as f32;
-    if p < 0.4 {
-        LifeState::Alive
-    } else if p < 0.65 {
-        LifeState::Declining
-    } else if p < 1. {
-        LifeState::Dying
+/// How a `Particle` ages, set by `ParticleConfig::with_life_curve` or `FireworkConfig::with_life_curve`
+///
+/// A `Particle` is `Alive` until `declining` of its life, `Declining` until `dying`, then `Dying` until it is `Dead`
+///
+/// # Notes
+///
+/// - `intensity` dims both the brightness and the density of the characters of the `Particle`
+/// - Thresholds are clamped so that 0 <= `declining` <= `dying` <= 1 when they are used
+/// - Two `LifeCurve`s are equal if their thresholds are, `intensity` functions are not compared
+#[derive(Debug, Clone, Copy)]
+pub struct LifeCurve {
+    /// Fraction of the life where the `Particle` starts `Declining`
+    pub declining: f32,
+    /// Fraction of the life where the `Particle` starts `Dying`
+    pub dying: f32,
+    /// Function that takes the fraction of the life from 0 to 1 and returns the intensity of the `Particle`
+    ///
+    /// `None` keeps the `Particle` at full intensity all over its life
+    pub intensity: Option<fn(f32) -> f32>,
+}
+
+impl PartialEq for LifeCurve {
+    fn eq(&self, other: &Self) -> bool {
+        self.thresholds() == other.thresholds()
+    }
+}
+
+impl Default for LifeCurve {
+    fn default() -> Self {
+        Self::new(0.4, 0.65)
+    }
+}
+
+impl LifeCurve {
+    /// Stays bright for most of its life and slowly fades, for willows and kamuros
+    pub const WILLOW: LifeCurve = LifeCurve {
+        declining: 0.7,
+        dying: 0.9,
+        intensity: Some(willow_intensity),
+    };
+
+    /// Burns at full intensity and goes out abruptly, for crackles
+    pub const ABRUPT: LifeCurve = LifeCurve {
+        declining: 0.85,
+        dying: 0.95,
+        intensity: Some(abrupt_intensity),
+    };
+
+    /// Create a new `LifeCurve` from its thresholds, without `intensity`
+    ///
+    /// # Arguments
+    ///
+    /// * `declining` - Fraction of the life where the `Particle` starts `Declining`
+    /// * `dying` - Fraction of the life where the `Particle` starts `Dying`
+    ///
+    /// # Notes
+    ///
+    /// - Thresholds are clamped so that 0 <= `declining` <= `dying` <= 1, NaN is taken as 0
+    pub fn new(declining: f32, dying: f32) -> Self {
+        let curve = Self {
+            declining,
+            dying,
+            intensity: None,
+        };
+        let (declining, dying) = curve.thresholds();
+        Self {
+            declining,
+            dying,
+            ..curve
+        }
+    }
+
+    /// Set `intensity`
+    #[inline]
+    #[must_use]
+    pub fn with_intensity(mut self, f: fn(f32) -> f32) -> Self {
+        self.intensity = Some(f);
+        self
+    }
+
+    /// Return `declining` and `dying` clamped so that 0 <= `declining` <= `dying` <= 1, NaN is taken as 0
+    pub fn thresholds(&self) -> (f32, f32) {
+        let clamp = |t: f32, min: f32| if t.is_nan() { min } else { t.clamp(min, 1.) };
+        let declining = clamp(self.declining, 0.);
+        (declining, clamp(self.dying, declining))
+    }
+
+    /// Return the `LifeState` at `life_fraction` of the life
+    pub fn life_state(&self, life_fraction: f32) -> LifeState {
+        let (declining, dying) = self.thresholds();
+        if life_fraction < declining {
+            LifeState::Alive
+        } else if life_fraction < dying {
+            LifeState::Declining
+        } else if life_fraction < 1. {
+            LifeState::Dying
+        } else {
+            LifeState::Dead
+        }
+    }
+
+    /// Return the intensity at `life_fraction` of the life, clamped between 0 and 1
+    pub fn intensity(&self, life_fraction: f32) -> f32 {
+        self.intensity
+            .map_or(1., |f| f(life_fraction.clamp(0., 1.)).clamp(0., 1.))
+    }
+}
+
+fn willow_intensity(t: f32) -> f32 {
+    1. - t.powi(4)
+}
+
+fn abrupt_intensity(t: f32) -> f32 {
+    if t < 0.85 {
+        1.
     } else {
-        LifeState::Dead
+        (1. - t) / 0.15
     }
 }
+
+pub(crate) fn cal_life_state(
+    life_time: Duration,
+    current_elapsed: Duration,
+    life_curve: &LifeCurve,
+) -> LifeState {
+    life_curve.life_state(current_elapsed.as_secs_f32() / life_time.as_secs_f32())
+}
@@ -0,0 +1,237 @@
+//! `scene` module provides background scenery drawn behind the fireworks
+
+use std::{f32::consts::PI, time::Duration};
+
+use glam::{Vec2, Vec3};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    fireworks::{FireworkManager, FireworkState},
+    particle::LifeState,
+    utils::cell_aspect,
+};
+
+/// Background drawn behind the fireworks, set by `Config::scene`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scene {
+    /// Black screen
+    #[default]
+    Blank,
+    /// Moon and twinkling stars
+    Night,
+    /// City skyline with lit windows under the moon and twinkling stars
+    ///
+    /// Buildings are lit up by bright bursts nearby
+    City,
+}
+
+/// Building wall color in RGB
+const WALL_COLOR: (u8, u8, u8) = (26, 28, 40);
+/// Lit window color in RGB
+const WINDOW_COLOR: (u8, u8, u8) = (200, 165, 85);
+/// Moon color in RGB
+const MOON_COLOR: (u8, u8, u8) = (235, 230, 200);
+/// Accumulated light a wall needs before it looks lit
+const LIGHT_THRESHOLD: f32 = 1.5;
+/// Part of the accumulated light kept from one frame to the next
+const LIGHT_DECAY: f32 = 0.8;
+
+/// One cell of the background
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Star { phase: f32, speed: f32 },
+    Moon { crater: bool },
+    Wall,
+    Window { lit: bool },
+}
+
+/// Procedurally generated background of a `Scene` for a terminal of a given size
+///
+/// The layout is generated once, stars twinkle with time and buildings keep the light of the bursts near them
+#[derive(Debug, Clone)]
+pub struct Scenery {
+    pub scene: Scene,
+    /// Size of the screen in cells, width and height
+    pub size: (usize, usize),
+    /// Background cells indexed as `cells[row][column]`
+    cells: Vec<Vec<Option<Cell>>>,
+    /// Top row of the buildings in every column
+    roofs: Vec<Option<usize>>,
+    /// Light accumulated by the buildings in every column, as RGB from 0 to 1 times intensity
+    light: Vec<Vec3>,
+}
+
+impl Scenery {
+    /// Generate the background of `scene` for a screen of `width` by `height` cells
+    ///
+    /// # Arguments
+    ///
+    /// * `cell_width` - Width of a cell in terminal columns, 1 for ascii and 2 for cjk, which keeps the moon round
+    /// * `seed` - Same `seed` gives the same layout
+    pub fn new(scene: Scene, width: usize, height: usize, cell_width: usize, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut cells = vec![vec![None; width]; height];
+        let mut roofs = vec![None; width];
+        if scene == Scene::City {
+            let window_period = if cell_width > 1 { 2 } else { 3 };
+            let mut x = 0;
+            while x < width {
+                // At least one column, so the skyline ends whatever `cell_width`
+                let w = (rng.gen_range(8..20) / cell_width).max(1);
+                let h = (height as f32 * rng.gen_range(0.12..0.35)) as usize;
+                let roof = height.saturating_sub(h);
+                for (bx, column) in (x..(x + w).min(width)).enumerate() {
+                    roofs[column] = Some(roof);
+                    for (by, row) in cells.iter_mut().enumerate().skip(roof) {
+                        let is_window = bx % window_period == 1
+                            && bx + 1 < w
+                            && (by - roof) % 2 == 1
+                            && by + 1 < height;
+                        row[column] = Some(if is_window {
+                            Cell::Window {
+                                lit: rng.gen_bool(0.3),
+                            }
+                        } else {
+                            Cell::Wall
+                        });
+                    }
+                }
+                x += w + (rng.gen_range(0..3) / cell_width).max(1);
+            }
+        }
+        if scene != Scene::Blank {
+            // Moon in the upper right
+            let r = (height as f32 * 0.08).max(1.5);
+            let (cx, cy) = (width as f32 * 0.8, height as f32 * 0.2);
+            let aspect = cell_aspect(cell_width) as f32;
+            for (y, row) in cells.iter_mut().enumerate() {
+                for (x, cell) in row.iter_mut().enumerate() {
+                    let dx = (x as f32 - cx) / aspect;
+                    let dy = y as f32 - cy;
+                    if dx * dx + dy * dy <= r * r {
+                        *cell = Some(Cell::Moon {
+                            crater: rng.gen_bool(0.15),
+                        });
+                    } else if cell.is_none() && rng.gen_bool(0.015 * cell_width as f64) {
+                        *cell = Some(Cell::Star {
+                            phase: rng.gen_range(0. ..2. * PI),
+                            speed: rng.gen_range(0.5..3.),
+                        });
+                    }
+                }
+            }
+        }
+        Self {
+            scene,
+            size: (width, height),
+            cells,
+            roofs,
+            light: vec![Vec3::ZERO; width],
+        }
+    }
+
+    /// Gather light of the bursts of `fm` near the buildings, call it once per frame
+    ///
+    /// # Arguments
+    ///
+    /// * `to_screen` - Position on the screen in columns and rows of a point in the simulation, `None` if it is not visible
+    pub fn update_light(&mut self, fm: &FireworkManager, to_screen: impl Fn(Vec3) -> Option<Vec2>) {
+        if self.scene != Scene::City {
+            return;
+        }
+        let mut frame = vec![Vec3::ZERO; self.size.0];
+        for firework in fm
+            .fireworks
+            .iter()
+            .filter(|f| f.state == FireworkState::Alive)
+        {
+            for p in firework.current_particles.iter() {
+                let brightness = match p.life_state {
+                    LifeState::Alive => 1.,
+                    LifeState::Declining => 0.6,
+                    LifeState::Dying => 0.3,
+                    LifeState::Dead => continue,
+                } * firework
+                    .config
+                    .life_curve_of(&p.config)
+                    .intensity(p.life_fraction());
+                let Some(pos) = to_screen(p.pos.extend(p.z)) else {
+                    continue;
+                };
+                let x = pos.x.round() as isize;
+                let (r, g, b) = p.config.color_at(p.life_fraction());
+                let color = Vec3::new(r as f32, g as f32, b as f32) / 255.;
+                for dx in -6..=6 {
+                    let column = x + dx;
+                    if column < 0 || column >= self.size.0 as isize {
+                        continue;
+                    }
+                    let Some(roof) = self.roofs[column as usize] else {
+                        continue;
+                    };
+                    let above = (roof as f32 - pos.y).max(0.);
+                    let falloff = 1. / (1. + above / 4.).powi(2) / (1. + dx.abs() as f32 / 2.);
+                    frame[column as usize] += color * brightness * falloff;
+                }
+            }
+        }
+        for (l, f) in self.light.iter_mut().zip(frame) {
+            *l = *l * LIGHT_DECAY + f * (1. - LIGHT_DECAY);
+        }
+    }
+
+    /// Return the character and RGB color of the background at `(x, y)`, or `None` if it is empty
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Time that makes the stars twinkle
+    pub fn cell(&self, x: usize, y: usize, time: Duration) -> Option<(char, (u8, u8, u8))> {
+        match (*self.cells.get(y)?.get(x)?)? {
+            Cell::Star { phase, speed } => {
+                let b = 0.5 + 0.5 * (time.as_secs_f32() * speed + phase).sin();
+                let c = if b > 0.85 {
+                    '*'
+                } else if b > 0.5 {
+                    '+'
+                } else {
+                    '.'
+                };
+                let v = (90. + 150. * b) as u8;
+                Some((c, (v, v, (v as f32 * 0.9 + 25.) as u8)))
+            }
+            Cell::Moon { crater } => Some(if crater {
+                ('o', scale(MOON_COLOR, 0.8))
+            } else {
+                ('@', MOON_COLOR)
+            }),
+            Cell::Wall => Some(('#', self.lit(x, WALL_COLOR))),
+            Cell::Window { lit: true } => Some(('#', WINDOW_COLOR)),
+            Cell::Window { lit: false } => Some(('#', self.lit(x, scale(WALL_COLOR, 0.7)))),
+        }
+    }
+
+    /// Blend `color` of a wall in column `x` with the light it has gathered
+    fn lit(&self, x: usize, color: (u8, u8, u8)) -> (u8, u8, u8) {
+        let light = self.light[x];
+        let intensity = light.max_element();
+        if intensity <= LIGHT_THRESHOLD {
+            return color;
+        }
+        let t = ((intensity - LIGHT_THRESHOLD) / LIGHT_THRESHOLD).min(1.) * 0.6;
+        let target = light / intensity * 255.;
+        let mix = |c: u8, l: f32| (c as f32 + (l - c as f32) * t) as u8;
+        (
+            mix(color.0, target.x),
+            mix(color.1, target.y),
+            mix(color.2, target.z),
+        )
+    }
+}
+
+fn scale(color: (u8, u8, u8), s: f32) -> (u8, u8, u8) {
+    (
+        (color.0 as f32 * s) as u8,
+        (color.1 as f32 * s) as u8,
+        (color.2 as f32 * s) as u8,
+    )
+}
@@ -0,0 +1,460 @@
+//! `shell` module provides a catalog of classic pyrotechnic shell types
+//!
+//! Every `ShellKind` is a preset of a `Shell`, whose size, colors and density can be changed before it is built into a `Firework`
+
+use std::{
+    f32::consts::PI,
+    ops::Range,
+    time::{Duration, SystemTime},
+};
+
+use glam::{Quat, Vec2, Vec3};
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+use crate::{
+    fireworks::{Firework, FireworkConfig},
+    particle::{Effect, LifeCurve, ParticleConfig},
+    utils::{
+        explosion_gradient_1, explosion_gradient_3, gen_vectors_fibonacci_sphere,
+        gen_vectors_ring_with_rng, gen_vectors_sphere_with_rng, linear_gradient_1, orientation,
+    },
+};
+
+/// Classic types of firework shells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    /// Sphere of plain stars without visible trails
+    Peony,
+    /// Sphere of stars leaving glittering trails
+    Chrysanthemum,
+    /// Long-lived golden trails drooping like willow branches
+    Willow,
+    /// A few thick arms like the leaves of a palm tree
+    Palm,
+    /// Ring of stars, tilted at random
+    Ring,
+    /// Two rings of stars crossing at right angles, tilted at random
+    DoubleRing,
+    /// Sphere of stars inside a tilted ring, like the planet
+    Saturn,
+    /// A few stars that split into crossing sparks
+    Crossette,
+    /// Stars thrown up and falling down together like a horse tail
+    Horsetail,
+    /// Dense glittering golden trails that hang long in the sky
+    Kamuro,
+    /// Single bright star rising with a long tail, `center` is where it is fired from
+    Comet,
+    /// Stars fired up from the ground in a fan, `center` is where they are fired from
+    Mine,
+    /// Stars wriggling away like swimming fish
+    Fish,
+}
+
+impl ShellKind {
+    /// All the `ShellKind`s of the catalog
+    pub const ALL: [ShellKind; 13] = [
+        ShellKind::Peony,
+        ShellKind::Chrysanthemum,
+        ShellKind::Willow,
+        ShellKind::Palm,
+        ShellKind::Ring,
+        ShellKind::DoubleRing,
+        ShellKind::Saturn,
+        ShellKind::Crossette,
+        ShellKind::Horsetail,
+        ShellKind::Kamuro,
+        ShellKind::Comet,
+        ShellKind::Mine,
+        ShellKind::Fish,
+    ];
+
+    /// Return the lowercase name of the `ShellKind`, e.g. `"willow"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShellKind::Peony => "peony",
+            ShellKind::Chrysanthemum => "chrysanthemum",
+            ShellKind::Willow => "willow",
+            ShellKind::Palm => "palm",
+            ShellKind::Ring => "ring",
+            ShellKind::DoubleRing => "double-ring",
+            ShellKind::Saturn => "saturn",
+            ShellKind::Crossette => "crossette",
+            ShellKind::Horsetail => "horsetail",
+            ShellKind::Kamuro => "kamuro",
+            ShellKind::Comet => "comet",
+            ShellKind::Mine => "mine",
+            ShellKind::Fish => "fish",
+        }
+    }
+
+    /// Return the `ShellKind` named `name`, ignoring case, or `None` if there is no such shell
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|k| k.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Return true if the shell is fired from the ground rather than exploding in the sky
+    pub fn is_ground(&self) -> bool {
+        matches!(self, ShellKind::Comet | ShellKind::Mine)
+    }
+
+    /// Return the default colors in RGB (from 0 to 255)
+    pub fn colors(&self) -> Vec<(u8, u8, u8)> {
+        match self {
+            ShellKind::Peony => vec![(255, 102, 75), (206, 32, 41), (255, 225, 124)],
+            ShellKind::Chrysanthemum => vec![(255, 240, 200), (250, 216, 68), (255, 175, 80)],
+            ShellKind::Willow | ShellKind::Kamuro => {
+                vec![(255, 215, 140), (240, 190, 110), (255, 240, 200)]
+            }
+            ShellKind::Palm => vec![(255, 200, 90), (255, 160, 60), (255, 230, 160)],
+            ShellKind::Ring | ShellKind::DoubleRing => {
+                vec![(89, 129, 177), (152, 186, 227), (240, 244, 254)]
+            }
+            ShellKind::Saturn => vec![(255, 175, 204), (189, 224, 254), (255, 248, 253)],
+            ShellKind::Crossette => vec![(255, 248, 253), (200, 230, 255)],
+            ShellKind::Horsetail => vec![(255, 225, 124), (255, 248, 253)],
+            ShellKind::Comet => vec![(255, 235, 180)],
+            ShellKind::Mine => vec![(87, 204, 153), (235, 39, 155), (250, 216, 68)],
+            ShellKind::Fish => vec![(162, 210, 255), (205, 180, 219), (255, 255, 255)],
+        }
+    }
+}
+
+/// A firework shell of a `ShellKind`, built into a `Firework` by `build`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shell {
+    pub kind: ShellKind,
+    /// Scale of the burst, 1 is the default size
+    pub size: f32,
+    /// Colors in RGB (from 0 to 255), every star picks one of them
+    pub colors: Vec<(u8, u8, u8)>,
+    /// Scale of the number of stars, 1 is the default density
+    pub density: f32,
+    /// If this is `true`, stars are evenly spread in a symmetrical pattern instead of at random
+    pub symmetric: bool,
+    /// If this is `true`, stars also move in depth for the 3D mode, otherwise the burst is flat
+    pub depth: bool,
+}
+
+impl Shell {
+    /// Create a new `Shell` of `kind` with its default size, colors and density
+    pub fn new(kind: ShellKind) -> Self {
+        Self {
+            kind,
+            size: 1.,
+            colors: kind.colors(),
+            density: 1.,
+            symmetric: false,
+            depth: false,
+        }
+    }
+
+    /// Set `size`
+    #[inline]
+    #[must_use]
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set `colors`, empty `colors` keep the default ones
+    #[inline]
+    #[must_use]
+    pub fn with_colors(mut self, colors: Vec<(u8, u8, u8)>) -> Self {
+        if !colors.is_empty() {
+            self.colors = colors;
+        }
+        self
+    }
+
+    /// Set `density`
+    #[inline]
+    #[must_use]
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Set `symmetric`
+    #[inline]
+    #[must_use]
+    pub fn with_symmetric(mut self, symmetric: bool) -> Self {
+        self.symmetric = symmetric;
+        self
+    }
+
+    /// Set `depth`
+    #[inline]
+    #[must_use]
+    pub fn with_depth(mut self, depth: bool) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Build the `Firework` of the `Shell`
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - Position of the burst, or the launch position of shells fired from the ground
+    /// * `spawn_after` - `Duration` before the `Firework` spawns
+    /// * `enable_gradient` - Whether the `Firework` has color gradient
+    pub fn build(&self, center: Vec2, spawn_after: Duration, enable_gradient: bool) -> Firework {
+        self.build_with_rng(center, spawn_after, enable_gradient, &mut thread_rng())
+    }
+
+    /// Build the `Firework` of the `Shell` with `rng`
+    ///
+    /// Same `rng` state gives the same `Firework`, see `build`
+    pub fn build_with_rng(
+        &self,
+        center: Vec2,
+        spawn_after: Duration,
+        enable_gradient: bool,
+        rng: &mut impl Rng,
+    ) -> Firework {
+        let symmetric = self.symmetric;
+        let n = |count: f32| ((count * self.density).round() as usize).max(1);
+        let speed = |s: f32| s * self.size;
+        let (velocities, trail, life, config) = match self.kind {
+            ShellKind::Peony => (
+                sphere(rng, symmetric, n(60.), speed(45.)),
+                10..15,
+                1.8..2.4,
+                FireworkConfig::default().with_gradient_scale(explosion_gradient_1),
+            ),
+            ShellKind::Chrysanthemum => (
+                sphere(rng, symmetric, n(80.), speed(50.)),
+                25..32,
+                2.2..2.8,
+                FireworkConfig::default().with_gradient_scale(explosion_gradient_1),
+            ),
+            ShellKind::Willow => (
+                sphere(rng, symmetric, n(70.), speed(35.)),
+                40..50,
+                4.0..5.5,
+                FireworkConfig::default()
+                    .with_gradient_scale(explosion_gradient_3)
+                    .with_gravity_scale(0.6)
+                    .with_ar_scale(0.4)
+                    .with_life_curve(LifeCurve::WILLOW),
+            ),
+            ShellKind::Palm => {
+                let arms = rng.gen_range(6..9);
+                let per_arm = n(6.);
+                let mut v = Vec::new();
+                for a in 0..arms {
+                    // Arms spread over the upper half and a bit below
+                    let angle = -0.1 * PI + 1.2 * PI * (a as f32 + 0.5) / arms as f32;
+                    for k in 0..per_arm {
+                        let (angle, scale) = if symmetric {
+                            (angle, 0.6 + 0.4 * (k as f32 + 1.) / per_arm as f32)
+                        } else {
+                            (angle + rng.gen_range(-0.03..0.03), rng.gen_range(0.6..1.))
+                        };
+                        v.push(Vec3::new(angle.cos(), -angle.sin(), 0.) * speed(40.) * scale);
+                    }
+                }
+                (
+                    v,
+                    30..38,
+                    2.5..3.2,
+                    FireworkConfig::default()
+                        .with_gradient_scale(explosion_gradient_1)
+                        .with_gravity_scale(0.7),
+                )
+            }
+            ShellKind::Ring => (
+                {
+                    let o = random_orientation(rng);
+                    gen_vectors_ring_with_rng(speed(40.), n(50.), o, rng)
+                },
+                12..16,
+                2.0..2.5,
+                FireworkConfig::default()
+                    .with_gradient_scale(explosion_gradient_1)
+                    .with_gravity_scale(0.4),
+            ),
+            ShellKind::DoubleRing => (
+                {
+                    let o = random_orientation(rng);
+                    let mut v = gen_vectors_ring_with_rng(speed(40.), n(40.), o, rng);
+                    v.extend(gen_vectors_ring_with_rng(
+                        speed(40.),
+                        n(40.),
+                        o * Quat::from_rotation_y(PI / 2.),
+                        rng,
+                    ));
+                    v
+                },
+                12..16,
+                2.0..2.5,
+                FireworkConfig::default()
+                    .with_gradient_scale(explosion_gradient_1)
+                    .with_gravity_scale(0.4),
+            ),
+            ShellKind::Saturn => (
+                {
+                    let mut v = sphere(rng, symmetric, n(50.), speed(25.));
+                    let o = random_orientation(rng);
+                    v.extend(gen_vectors_ring_with_rng(speed(45.), n(50.), o, rng));
+                    v
+                },
+                10..14,
+                2.0..2.5,
+                FireworkConfig::default()
+                    .with_gradient_scale(explosion_gradient_1)
+                    .with_gravity_scale(0.4),
+            ),
+            ShellKind::Crossette => (
+                sphere(rng, symmetric, n(10.), speed(40.)),
+                15..20,
+                2.0..2.4,
+                FireworkConfig::default().with_gradient_scale(linear_gradient_1),
+            ),
+            ShellKind::Horsetail => (
+                fan(
+                    rng,
+                    symmetric,
+                    n(40.),
+                    0.35 * PI..0.65 * PI,
+                    speed(15.)..speed(25.),
+                ),
+                35..45,
+                3.0..4.0,
+                FireworkConfig::default()
+                    .with_gradient_scale(explosion_gradient_3)
+                    .with_gravity_scale(1.2)
+                    .with_ar_scale(0.2),
+            ),
+            ShellKind::Kamuro => (
+                sphere(rng, symmetric, n(90.), speed(40.)),
+                35..45,
+                4.5..6.0,
+                FireworkConfig::default()
+                    .with_gradient_scale(explosion_gradient_3)
+                    .with_gravity_scale(0.5)
+                    .with_ar_scale(0.45)
+                    .with_life_curve(LifeCurve::WILLOW),
+            ),
+            ShellKind::Comet => (
+                fan(
+                    rng,
+                    symmetric,
+                    n(1.),
+                    0.47 * PI..0.53 * PI,
+                    speed(40.)..speed(45.),
+                ),
+                50..60,
+                1.8..2.2,
+                FireworkConfig::default()
+                    .with_gradient_scale(linear_gradient_1)
+                    .with_ar_scale(0.1),
+            ),
+            ShellKind::Mine => (
+                fan(
+                    rng,
+                    symmetric,
+                    n(50.),
+                    0.3 * PI..0.7 * PI,
+                    speed(30.)..speed(50.),
+                ),
+                12..18,
+                1.5..2.2,
+                FireworkConfig::default().with_gradient_scale(explosion_gradient_1),
+            ),
+            ShellKind::Fish => (
+                sphere(rng, symmetric, n(30.), speed(35.)),
+                6..9,
+                2.5..3.2,
+                FireworkConfig::default()
+                    .with_gradient_scale(explosion_gradient_1)
+                    .with_gravity_scale(0.3)
+                    .with_ar_scale(0.3)
+                    .with_additional_force(|ctx| {
+                        // Every fish wriggles sideways with its own phase
+                        let phase = ctx.index as f32 * 2.4;
+                        let t = ctx.particle.time_elapsed.as_secs_f32();
+                        ctx.particle.vel.perp().normalize_or_zero() * 60. * (t * 12. + phase).sin()
+                    }),
+            ),
+        };
+        let effect = match self.kind {
+            ShellKind::Chrysanthemum => Effect::Glitter { density: 0.5 },
+            ShellKind::Kamuro => Effect::Glitter { density: 0.7 },
+            ShellKind::Crossette => Effect::Crackle {
+                sparks: 4,
+                speed: speed(20.),
+            },
+            _ => Effect::None,
+        };
+        let depth = self.depth;
+        let particles = velocities
+            .into_iter()
+            .map(|v| {
+                ParticleConfig::new(
+                    center,
+                    v.truncate(),
+                    rng.gen_range(trail.clone()),
+                    Duration::from_secs_f32(rng.gen_range(life.clone())),
+                    *self.colors.choose(rng).unwrap_or(&(255, 255, 255)),
+                )
+                .with_effect(effect)
+                .with_depth(0., if depth { v.z } else { 0. })
+            })
+            .collect();
+        let mut config = config;
+        config.set_enable_gradient(enable_gradient);
+        Firework {
+            init_time: SystemTime::now(),
+            spawn_after,
+            center,
+            particles,
+            config,
+            ..Default::default()
+        }
+    }
+}
+
+/// Generate `n` velocities of stars of a spherical shell, with at most `speed`
+fn sphere(rng: &mut impl Rng, symmetric: bool, n: usize, speed: f32) -> Vec<Vec3> {
+    if symmetric {
+        return gen_vectors_fibonacci_sphere(speed, n);
+    }
+    gen_vectors_sphere_with_rng(speed, n, rng)
+        .into_iter()
+        .map(|v| v * rng.gen_range(0.9..1.))
+        .collect()
+}
+
+/// Return a random orientation of a ring, which never faces the viewer edge-on
+fn random_orientation(rng: &mut impl Rng) -> Quat {
+    orientation(rng.gen_range(0. ..0.42 * PI), rng.gen_range(0. ..2. * PI))
+}
+
+/// Generate `n` flat velocities within the `angle` range, counterclockwise from the right, with `speed` in range
+///
+/// Symmetrical velocities are evenly spaced, with the average speed
+fn fan(
+    rng: &mut impl Rng,
+    symmetric: bool,
+    n: usize,
+    angle: Range<f32>,
+    speed: Range<f32>,
+) -> Vec<Vec3> {
+    (0..n)
+        .map(|i| {
+            let (a, s) = if symmetric {
+                let t = (i as f32 + 0.5) / n as f32;
+                (
+                    angle.start + (angle.end - angle.start) * t,
+                    (speed.start + speed.end) / 2.,
+                )
+            } else {
+                (rng.gen_range(angle.clone()), rng.gen_range(speed.clone()))
+            };
+            Vec3::new(a.cos(), -a.sin(), 0.) * s
+        })
+        .collect()
+}
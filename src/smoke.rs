@@ -0,0 +1,100 @@
+//! `smoke` module provides smoke clouds that linger after the bursts
+
+use std::{f32::consts::PI, time::Duration};
+
+use glam::Vec2;
+use rand::Rng;
+
+use crate::{fireworks::FireworkConfig, particle::ParticleConfig};
+
+/// Configuration of the smoke left by a `Firework`, set by `FireworkConfig::with_smoke`
+///
+/// Smoke puffs are particles of their own, which spread slowly from the explosion site, rise a little,
+/// drift with the `ForceField`s and fade out
+///
+/// # Notes
+///
+/// - An `Instant` firework leaves `count` puffs when it explodes
+/// - A `Sustained` firework leaves `count` puffs every `interval` while it emits particles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmokeConfig {
+    /// Number of puffs of a cloud
+    pub count: usize,
+    /// Initial speed of the fastest puffs, which decides the size of a cloud
+    pub spread: f32,
+    /// `Duration` from a puff's spawn to its end
+    pub life_time: Duration,
+    /// Color in RGB (from 0 to 255) of the thickest smoke
+    pub color: (u8, u8, u8),
+    /// Gravity scale of the puffs, negative makes them rise
+    pub gravity_scale: f32,
+    /// Air resistance scale of the puffs, high to make them slow down quickly
+    pub ar_scale: f32,
+    /// Time interval between two clouds of a `Sustained` firework
+    pub interval: Duration,
+}
+
+impl Default for SmokeConfig {
+    fn default() -> Self {
+        Self {
+            count: 24,
+            spread: 8.,
+            life_time: Duration::from_secs(4),
+            color: (85, 85, 95),
+            gravity_scale: -0.03,
+            ar_scale: 0.6,
+            interval: Duration::from_millis(300),
+        }
+    }
+}
+
+impl SmokeConfig {
+    /// Set `count` and `spread`
+    #[inline]
+    #[must_use]
+    pub fn with_size(mut self, count: usize, spread: f32) -> Self {
+        self.count = count;
+        self.spread = spread;
+        self
+    }
+
+    /// Set `life_time`
+    #[inline]
+    #[must_use]
+    pub fn with_life_time(mut self, life_time: Duration) -> Self {
+        self.life_time = life_time;
+        self
+    }
+
+    /// Set `color`
+    #[inline]
+    #[must_use]
+    pub fn with_color(mut self, color: (u8, u8, u8)) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Return the `FireworkConfig` that moves the puffs
+    pub(crate) fn physics(&self) -> FireworkConfig {
+        FireworkConfig::default()
+            .with_gravity_scale(self.gravity_scale)
+            .with_ar_scale(self.ar_scale)
+    }
+
+    /// Generate the `ParticleConfig`s of a cloud at `center`, drawing from `rng`
+    pub(crate) fn gen_puffs(&self, center: Vec2, rng: &mut impl Rng) -> Vec<ParticleConfig> {
+        (0..self.count)
+            .map(|_| {
+                let a = rng.gen_range(0. ..2. * PI);
+                let dir = Vec2::new(a.cos(), a.sin());
+                ParticleConfig::new(
+                    center + dir * rng.gen_range(0. ..1.),
+                    dir * self.spread * rng.gen_range(0.3..1.),
+                    0,
+                    self.life_time.mul_f32(rng.gen_range(0.7..1.)),
+                    self.color,
+                )
+            })
+            .collect()
+    }
+}
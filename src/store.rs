@@ -0,0 +1,588 @@
+//! `store` module provides a pooled structure-of-arrays storage of particles
+
+use std::{f32::consts::PI, time::Duration};
+
+use glam::{Vec2, Vec3};
+use rand::Rng;
+
+use crate::{
+    boundary::{Boundary, Collision, Ground, GroundBehavior},
+    chemistry::Emission,
+    field::{total_force, ForceField},
+    fireworks::FireworkConfig,
+    particle::{
+        advance, cal_life_state, Effect, ForceContext, LifeCurve, LifeState, Particle,
+        ParticleConfig, ParticleRng, UserData,
+    },
+};
+
+/// Minimum number of slots handled by one thread, smaller stores are not worth splitting
+#[cfg(feature = "rayon")]
+const PAR_MIN_LEN: usize = 512;
+
+/// Simulation steps that all the `Particle`s go through in one update
+#[derive(Clone, Copy)]
+pub struct Steps<'a> {
+    /// Simulation time at the beginning of the first step
+    pub time: Duration,
+    /// `Duration` of a step
+    pub dt: Duration,
+    /// Number of steps
+    pub count: u32,
+    /// `ForceField`s applied to all the `Particle`s
+    pub fields: &'a [ForceField],
+    /// Ground and walls that `Particle`s collide with
+    pub boundary: &'a Boundary,
+    /// Center of the `Firework` being updated
+    pub center: Vec2,
+    /// `Duration` since the `Firework` being updated spawned, at the beginning of the first step
+    pub firework_age: Duration,
+}
+
+impl<'a> Steps<'a> {
+    /// Return the steps left after the first `k` ones
+    pub fn skip(&self, k: u32) -> Self {
+        let k = k.min(self.count);
+        Self {
+            time: self.time + self.dt * k,
+            firework_age: self.firework_age + self.dt * k,
+            count: self.count - k,
+            ..*self
+        }
+    }
+}
+
+/// Pooled structure-of-arrays storage of the `Particle`s of a `Firework`
+///
+/// Every field of the `Particle`s is kept in its own array, and all the trails share one ring-buffer allocation
+/// Slots of dead `Particle`s are reused by the next spawned ones, so a running show doesn't allocate
+///
+/// # Notes
+///
+/// - Use `get` or `iter` to read a `Particle`, and `trail` to read its trail
+/// - Slots keep their index for the whole life of a `Particle`
+#[derive(Debug, Clone, Default)]
+pub struct ParticleStore {
+    pos: Vec<Vec2>,
+    vel: Vec<Vec2>,
+    z: Vec<f32>,
+    vel_z: Vec<f32>,
+    time_elapsed: Vec<Duration>,
+    life_state: Vec<LifeState>,
+    config: Vec<ParticleConfig>,
+    user_data: Vec<UserData>,
+    alive: Vec<bool>,
+    /// Indices of dead slots, ready to be reused
+    free: Vec<usize>,
+    /// Trails of all the slots with depth, slot `i` owns `trails[i * trail_stride..(i + 1) * trail_stride]`
+    trails: Vec<Vec3>,
+    trail_stride: usize,
+    /// Index of the oldest position in the trail ring buffer of every slot
+    trail_head: Vec<usize>,
+    len: usize,
+}
+
+impl ParticleStore {
+    /// Create a new empty `ParticleStore`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new empty `ParticleStore` with room for `capacity` `Particle`s with trails of `trail_length`
+    pub fn with_capacity(capacity: usize, trail_length: usize) -> Self {
+        Self {
+            pos: Vec::with_capacity(capacity),
+            vel: Vec::with_capacity(capacity),
+            z: Vec::with_capacity(capacity),
+            vel_z: Vec::with_capacity(capacity),
+            time_elapsed: Vec::with_capacity(capacity),
+            life_state: Vec::with_capacity(capacity),
+            config: Vec::with_capacity(capacity),
+            user_data: Vec::with_capacity(capacity),
+            alive: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            trails: Vec::with_capacity(capacity * trail_length),
+            trail_stride: trail_length,
+            trail_head: Vec::with_capacity(capacity),
+            len: 0,
+        }
+    }
+
+    /// Return the number of alive `Particle`s
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if there is no alive `Particle`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the number of slots, including the free ones
+    pub fn capacity(&self) -> usize {
+        self.alive.len()
+    }
+
+    /// Spawn a new `Particle` from its `ParticleConfig`, returning the index of its slot
+    pub fn spawn(&mut self, config: ParticleConfig) -> usize {
+        if config.trail_length > self.trail_stride {
+            self.grow_trails(config.trail_length);
+        }
+        let i = match self.free.pop() {
+            Some(i) => {
+                self.pos[i] = config.init_pos;
+                self.vel[i] = config.init_vel;
+                self.z[i] = config.init_z;
+                self.vel_z[i] = config.init_vel_z;
+                self.time_elapsed[i] = Duration::ZERO;
+                self.life_state[i] = LifeState::Alive;
+                self.config[i] = config;
+                self.user_data[i] = UserData::default();
+                self.alive[i] = true;
+                i
+            }
+            None => {
+                self.pos.push(config.init_pos);
+                self.vel.push(config.init_vel);
+                self.z.push(config.init_z);
+                self.vel_z.push(config.init_vel_z);
+                self.time_elapsed.push(Duration::ZERO);
+                self.life_state.push(LifeState::Alive);
+                self.config.push(config);
+                self.user_data.push(UserData::default());
+                self.alive.push(true);
+                self.trail_head.push(0);
+                self.trails
+                    .resize(self.trails.len() + self.trail_stride, Vec3::ZERO);
+                self.alive.len() - 1
+            }
+        };
+        self.trail_head[i] = 0;
+        let st = i * self.trail_stride;
+        self.trails[st..st + self.trail_stride].fill(config.init_pos.extend(config.init_z));
+        self.len += 1;
+        i
+    }
+
+    /// Kill all the `Particle`s, keeping the allocated memory for later use
+    pub fn clear(&mut self) {
+        self.free.clear();
+        self.free.extend((0..self.alive.len()).rev());
+        self.alive.fill(false);
+        self.life_state.fill(LifeState::Dead);
+        self.len = 0;
+    }
+
+    /// Return a copy of the `Particle` in slot `i`, or `None` if the slot is free
+    pub fn get(&self, i: usize) -> Option<Particle> {
+        if !*self.alive.get(i)? {
+            return None;
+        }
+        Some(Particle {
+            pos: self.pos[i],
+            vel: self.vel[i],
+            z: self.z[i],
+            vel_z: self.vel_z[i],
+            life_state: self.life_state[i],
+            time_elapsed: self.time_elapsed[i],
+            config: self.config[i],
+        })
+    }
+
+    /// Return `UserData` of the `Particle` in slot `i`, which is changed by `additional_force`
+    pub fn user_data(&self, i: usize) -> &UserData {
+        &self.user_data[i]
+    }
+
+    /// Return mutable `UserData` of the `Particle` in slot `i`
+    pub fn user_data_mut(&mut self, i: usize) -> &mut UserData {
+        &mut self.user_data[i]
+    }
+
+    /// Return indices of the slots of all alive `Particle`s in ascending order
+    pub fn indices(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.alive
+            .iter()
+            .enumerate()
+            .filter_map(|(i, a)| a.then_some(i))
+    }
+
+    /// Return copies of all alive `Particle`s
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Particle> + '_ {
+        self.indices().filter_map(|i| self.get(i))
+    }
+
+    /// Return the trail of the `Particle` in slot `i`, from the oldest position to the newest
+    ///
+    /// The trail has `trail_length` positions of its `ParticleConfig`
+    pub fn trail(&self, i: usize) -> impl DoubleEndedIterator<Item = Vec2> + '_ {
+        self.trail_3d(i).map(|p| p.truncate())
+    }
+
+    /// Return the trail of the `Particle` in slot `i` with depth, from the oldest position to the newest
+    pub fn trail_3d(&self, i: usize) -> impl DoubleEndedIterator<Item = Vec3> + '_ {
+        let len = self.config[i].trail_length;
+        let ring = &self.trails[i * self.trail_stride..i * self.trail_stride + len];
+        let head = self.trail_head[i].min(len.saturating_sub(1));
+        ring[head..].iter().chain(ring[..head].iter()).copied()
+    }
+
+    /// Advance all alive `Particle`s by `steps`, and free the slots of `Dead` ones
+    ///
+    /// `Particle`s are independent of each other, so each one goes through all the steps before the next one,
+    /// which gives the same result as stepping all of them together and keeps the data in cache
+    ///
+    /// # Notes
+    ///
+    /// - With the `rayon` feature, `Particle`s are advanced on all threads, with bit-identical results
+    pub fn advance(&mut self, steps: &Steps, config: &FireworkConfig) {
+        #[cfg(feature = "rayon")]
+        let bursts = {
+            use rayon::prelude::*;
+            (
+                self.pos.par_iter_mut(),
+                self.vel.par_iter_mut(),
+                self.z.par_iter_mut(),
+                self.vel_z.par_iter_mut(),
+                self.time_elapsed.par_iter_mut(),
+                self.life_state.par_iter_mut(),
+                self.user_data.par_iter_mut(),
+                self.config.par_iter(),
+                self.alive.par_iter(),
+            )
+                .into_par_iter()
+                .with_min_len(PAR_MIN_LEN)
+                .enumerate()
+                .filter(|(_, (.., alive))| **alive)
+                .filter_map(
+                    |(
+                        i,
+                        (
+                            pos,
+                            vel,
+                            z,
+                            vel_z,
+                            time_elapsed,
+                            life_state,
+                            user_data,
+                            particle_config,
+                            _,
+                        ),
+                    )| {
+                        integrate(
+                            Slot {
+                                index: i,
+                                pos,
+                                vel,
+                                z,
+                                vel_z,
+                                time_elapsed,
+                                life_state,
+                                user_data,
+                                config: particle_config,
+                            },
+                            steps,
+                            config,
+                        )
+                    },
+                )
+                .collect::<Vec<_>>()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let bursts = {
+            let mut bursts = Vec::new();
+            for i in 0..self.alive.len() {
+                if self.alive[i] {
+                    bursts.extend(integrate(self.slot(i), steps, config));
+                }
+            }
+            bursts
+        };
+        // Slots are freed in ascending order whatever the threads, so later spawns reuse the same slots
+        for i in 0..self.alive.len() {
+            self.free_if_dead(i);
+        }
+        for burst in bursts {
+            self.burst(burst, steps, config);
+        }
+    }
+
+    /// Advance the `Particle` in slot `i` by `steps`, and free its slot if it is `Dead`
+    pub fn advance_slot(&mut self, i: usize, steps: &Steps, config: &FireworkConfig) {
+        if !self.alive[i] {
+            return;
+        }
+        let burst = integrate(self.slot(i), steps, config);
+        self.free_if_dead(i);
+        if let Some(burst) = burst {
+            self.burst(burst, steps, config);
+        }
+    }
+
+    /// Spawn the sparks of a `Burst`, they move for the rest of `steps`
+    fn burst(&mut self, burst: Burst, steps: &Steps, config: &FireworkConfig) {
+        let time = steps.time + steps.dt * burst.step;
+        let mut rng = ParticleRng::new(&burst.particle, burst.index, time);
+        let rest = steps.skip(burst.step + 1);
+        let parent = burst.particle;
+        let sparks = match burst.kind {
+            BurstKind::Splash { vel } => {
+                // Sparks fly up from the ground
+                let Some(Ground {
+                    behavior: GroundBehavior::Splash { sparks, speed, .. },
+                    ..
+                }) = steps.boundary.ground
+                else {
+                    return;
+                };
+                let impact = vel.length() * speed;
+                (0..sparks)
+                    .map(|_| {
+                        let angle = rng.gen_range(0.15 * PI..0.85 * PI);
+                        ParticleConfig {
+                            init_pos: parent.pos,
+                            init_vel: Vec2::new(angle.cos(), -angle.sin())
+                                * impact
+                                * rng.gen_range(0.5..1.),
+                            trail_length: parent.config.trail_length.min(3),
+                            life_time: Duration::from_secs_f32(rng.gen_range(0.3..0.8)),
+                            color: parent.config.color_at(parent.life_fraction()),
+                            emission: Emission::Fixed,
+                            effect: Effect::None,
+                            init_z: parent.z,
+                            init_vel_z: 0.,
+                            life_curve: None,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }
+            BurstKind::Crackle { sparks, speed } => {
+                // Sparks fly in all directions, carried by the particle and lighter than it
+                (0..sparks)
+                    .map(|_| {
+                        let angle = rng.gen_range(0. ..2. * PI);
+                        ParticleConfig {
+                            init_pos: parent.pos,
+                            init_vel: parent.vel * 0.3
+                                + Vec2::new(angle.cos(), angle.sin())
+                                    * speed
+                                    * rng.gen_range(0.5..1.),
+                            trail_length: 2,
+                            life_time: Duration::from_secs_f32(rng.gen_range(0.1..0.3)),
+                            color: lighten(parent.config.color_at(parent.life_fraction())),
+                            emission: Emission::Fixed,
+                            effect: Effect::None,
+                            init_z: parent.z,
+                            init_vel_z: parent.vel_z * 0.3,
+                            life_curve: Some(LifeCurve::ABRUPT),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+        for spark in sparks {
+            let i = self.spawn(spark);
+            self.advance_slot(i, &rest, config);
+        }
+    }
+
+    /// Borrow the fields of slot `i`
+    fn slot(&mut self, i: usize) -> Slot<'_> {
+        Slot {
+            index: i,
+            pos: &mut self.pos[i],
+            vel: &mut self.vel[i],
+            z: &mut self.z[i],
+            vel_z: &mut self.vel_z[i],
+            time_elapsed: &mut self.time_elapsed[i],
+            life_state: &mut self.life_state[i],
+            user_data: &mut self.user_data[i],
+            config: &self.config[i],
+        }
+    }
+
+    /// Free slot `i` if its `Particle` has just turned `Dead`
+    fn free_if_dead(&mut self, i: usize) {
+        if self.alive[i] && self.life_state[i] == LifeState::Dead {
+            self.alive[i] = false;
+            self.free.push(i);
+            self.len -= 1;
+        }
+    }
+
+    /// Record current positions of all alive `Particle`s in their trails, dropping the oldest ones
+    pub fn record_trails(&mut self) {
+        for i in 0..self.alive.len() {
+            if !self.alive[i] {
+                continue;
+            }
+            let len = self.config[i].trail_length;
+            if len == 0 {
+                continue;
+            }
+            let head = self.trail_head[i];
+            self.trails[i * self.trail_stride + head] = self.pos[i].extend(self.z[i]);
+            self.trail_head[i] = (head + 1) % len;
+        }
+    }
+
+    /// Re-layout the trails so that every slot can hold `trail_length` positions
+    fn grow_trails(&mut self, trail_length: usize) {
+        let old = self.trail_stride;
+        let mut trails = vec![Vec3::ZERO; self.alive.len() * trail_length];
+        for i in 0..self.alive.len() {
+            trails[i * trail_length..i * trail_length + old]
+                .copy_from_slice(&self.trails[i * old..(i + 1) * old]);
+        }
+        self.trails = trails;
+        self.trail_stride = trail_length;
+    }
+}
+
+/// Fields of one slot of a `ParticleStore`
+struct Slot<'a> {
+    index: usize,
+    pos: &'a mut Vec2,
+    vel: &'a mut Vec2,
+    z: &'a mut f32,
+    vel_z: &'a mut f32,
+    time_elapsed: &'a mut Duration,
+    life_state: &'a mut LifeState,
+    user_data: &'a mut UserData,
+    config: &'a ParticleConfig,
+}
+
+/// A `Particle` that died throwing up sparks
+struct Burst {
+    /// The `Particle` at the end of the step
+    particle: Particle,
+    index: usize,
+    /// Index of the step of the burst
+    step: u32,
+    kind: BurstKind,
+}
+
+/// What made a `Particle` burst into sparks
+enum BurstKind {
+    /// It hit a `Ground` that splashes, `vel` is its velocity at the impact
+    Splash { vel: Vec2 },
+    /// It popped with `Effect::Crackle`
+    Crackle { sparks: usize, speed: f32 },
+}
+
+/// Move one `Particle` forward by `steps`, stopping as soon as it is `Dead`
+///
+/// Return the `Burst` if it died splashing on the ground or crackling
+fn integrate(slot: Slot, steps: &Steps, config: &FireworkConfig) -> Option<Burst> {
+    let Slot {
+        index,
+        pos,
+        vel,
+        z,
+        vel_z,
+        time_elapsed,
+        life_state,
+        user_data,
+        config: particle_config,
+    } = slot;
+    let dt_secs = steps.dt.as_secs_f32();
+    for k in 0..steps.count {
+        let time = steps.time + steps.dt * k;
+        *time_elapsed += steps.dt;
+        *life_state = cal_life_state(
+            particle_config.life_time,
+            *time_elapsed,
+            &config.life_curve_of(particle_config),
+        );
+        if *life_state == LifeState::Dead {
+            break;
+        }
+        if let Effect::Crackle { sparks, speed } = particle_config.effect {
+            if time_elapsed.as_secs_f32()
+                >= particle_config.life_time.as_secs_f32() * Effect::CRACKLE_AT
+            {
+                let particle = Particle {
+                    pos: *pos,
+                    vel: *vel,
+                    z: *z,
+                    vel_z: *vel_z,
+                    life_state: *life_state,
+                    time_elapsed: *time_elapsed,
+                    config: *particle_config,
+                };
+                *time_elapsed = particle_config.life_time;
+                *life_state = LifeState::Dead;
+                return Some(Burst {
+                    particle,
+                    index,
+                    step: k,
+                    kind: BurstKind::Crackle { sparks, speed },
+                });
+            }
+        }
+        let mut additional = match &config.additional_force {
+            Some(f) => {
+                let particle = Particle {
+                    pos: *pos,
+                    vel: *vel,
+                    z: *z,
+                    vel_z: *vel_z,
+                    life_state: *life_state,
+                    time_elapsed: *time_elapsed,
+                    config: *particle_config,
+                };
+                f(&mut ForceContext {
+                    particle: &particle,
+                    time,
+                    dt: steps.dt,
+                    center: steps.center,
+                    firework_age: steps.firework_age + steps.dt * k,
+                    index,
+                    user_data,
+                    rng: ParticleRng::new(&particle, index, time),
+                })
+            }
+            None => Vec2::ZERO,
+        };
+        if !steps.fields.is_empty() {
+            additional += total_force(steps.fields, *pos, *vel, time);
+        }
+        advance(pos, vel, z, vel_z, dt_secs, additional, config);
+        match steps.boundary.collide(pos, vel) {
+            None => {}
+            Some(Collision::Settle { ember_time }) => {
+                *vel_z = 0.;
+                *time_elapsed =
+                    (*time_elapsed).max(particle_config.life_time.saturating_sub(ember_time));
+            }
+            Some(c) => {
+                *time_elapsed = particle_config.life_time;
+                *life_state = LifeState::Dead;
+                return match c {
+                    Collision::Splash { vel } => Some(Burst {
+                        particle: Particle {
+                            pos: *pos,
+                            vel,
+                            z: *z,
+                            vel_z: *vel_z,
+                            life_state: *life_state,
+                            time_elapsed: *time_elapsed,
+                            config: *particle_config,
+                        },
+                        index,
+                        step: k,
+                        kind: BurstKind::Splash { vel },
+                    }),
+                    _ => None,
+                };
+            }
+        }
+    }
+    None
+}
+
+/// Return `color` halfway to white, sparks look hotter than the `Particle` they come from
+fn lighten(color: (u8, u8, u8)) -> (u8, u8, u8) {
+    let l = |c: u8| c / 2 + 128;
+    (l(color.0), l(color.1), l(color.2))
+}
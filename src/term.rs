@@ -1,18 +1,24 @@
 //! `term` module provides functions of rendering in terminal
 
-use std::io::{Stdout, Write};
+use std::{io::Write, time::Duration};
 
 use crossterm::{cursor::MoveTo, queue, style, terminal};
-use glam::Vec2;
-use rand::{seq::IteratorRandom, thread_rng};
+use glam::{Vec2, Vec3};
+use rand::{thread_rng, Rng};
 
 use crate::{
     config::Config,
     fireworks::{FireworkManager, FireworkState},
-    particle::LifeState,
-    utils::distance_squared,
+    glyph::{char_width, GlyphPalette},
+    particle::{Effect, LifeState, Particle},
+    scene::{Scene, Scenery},
+    text::{TextLayer, TextOverlay, TextPosition},
+    utils::{distance_squared, round},
 };
 
+/// Text of the cell covered by the right half of a double-width glyph on its left, which is not printed
+pub const WIDE_TAIL: char = '\0';
+
 /// Wrap a character with color
 #[derive(Debug, Clone, Copy)]
 pub struct Char {
@@ -28,26 +34,70 @@ impl Char {
     }
 }
 
+/// Water surface below a horizon line, which mirrors the sky above it with dimmed and rippled colors
+///
+/// Apply it with `Terminal::render_reflection` after `Terminal::render`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reflection {
+    /// Position of the horizon line from the top, as a fraction of the terminal height
+    pub horizon: f32,
+    /// Brightness of the reflection, from 0 to 1
+    pub brightness: f32,
+    /// Largest horizontal shift of the ripples in cells, ripples grow further from the horizon
+    pub ripple: f32,
+    /// How fast the ripples move
+    pub ripple_speed: f32,
+}
+
+impl Default for Reflection {
+    fn default() -> Self {
+        Self {
+            horizon: 0.75,
+            brightness: 0.5,
+            ripple: 1.5,
+            ripple_speed: 2.,
+        }
+    }
+}
+
+impl Reflection {
+    /// Create a new `Reflection` with the horizon at `horizon` of the terminal height
+    pub fn new(horizon: f32) -> Self {
+        Self {
+            horizon,
+            ..Default::default()
+        }
+    }
+
+    /// Set `brightness`
+    #[inline]
+    #[must_use]
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness;
+        self
+    }
+
+    /// Set `ripple` and `ripple_speed`
+    #[inline]
+    #[must_use]
+    pub fn with_ripple(mut self, ripple: f32, ripple_speed: f32) -> Self {
+        self.ripple = ripple;
+        self.ripple_speed = ripple_speed;
+        self
+    }
+}
+
 /// Struct that represents a terminal
 pub struct Terminal {
     pub size: (u16, u16),
     pub screen: Vec<Vec<Char>>,
+    /// Background of `Config::scene`, generated on the first `render` and after the screen is resized
+    pub scenery: Option<Scenery>,
 }
 
 impl Default for Terminal {
     fn default() -> Self {
-        let size = terminal::size().expect("Fail to get terminal size.");
-        let screen = vec![
-            vec![
-                Char {
-                    text: ' ',
-                    color: style::Color::White
-                };
-                size.0 as usize
-            ];
-            size.1 as usize
-        ];
-        Self { size, screen }
+        Self::from_size(terminal::size().expect("Fail to get terminal size."))
     }
 }
 
@@ -57,27 +107,19 @@ impl Terminal {
         if cfg.enable_cjk {
             size.0 = (size.0 - 1) / 2;
         }
-        let screen = vec![
-            vec![
-                Char {
-                    text: ' ',
-                    color: style::Color::White
-                };
-                size.0 as usize
-            ];
-            size.1 as usize
-        ];
-        Self { size, screen }
+        Self::from_size(size)
     }
 
     /// Reload terminal to adapt new window size
     pub fn reinit(&mut self, cfg: &Config) {
-        let mut size = terminal::size().expect("Fail to get terminal size.");
-        if cfg.enable_cjk {
-            size.0 = (size.0 - 1) / 2;
-        }
-        self.size = size;
-        self.screen = vec![
+        *self = Self::new(cfg);
+    }
+
+    /// Create a new `Terminal` of `size` cells, whatever the size of the real terminal
+    ///
+    /// Cells are two columns wide when cjk is enabled, so `size.0` is half the number of columns
+    pub fn from_size(size: (u16, u16)) -> Self {
+        let screen = vec![
             vec![
                 Char {
                     text: ' ',
@@ -87,122 +129,476 @@ impl Terminal {
             ];
             size.1 as usize
         ];
+        Self {
+            size,
+            screen,
+            scenery: None,
+        }
     }
 
     /// Clear the terminal screen by setting all the characters in terminal to space
     pub fn clear_screen(&mut self) {
-        let size = terminal::size().expect("Fail to get terminal size.");
         self.screen = vec![
             vec![
                 Char {
                     text: ' ',
                     color: style::Color::White
                 };
-                size.0 as usize
+                self.size.0 as usize
             ];
-            size.1 as usize
+            self.size.1 as usize
         ];
     }
 
     /// Print the data out to terminal
-    pub fn print(&self, w: &mut Stdout, cfg: &Config) {
-        self.screen.iter().enumerate().for_each(|(y, line)| {
-            line.iter().enumerate().for_each(|(x, c)| {
-                queue!(
-                    w,
-                    MoveTo(
-                        if cfg.enable_cjk {
-                            (x * 2) as u16
-                        } else {
-                            x as u16
-                        },
-                        y as u16
-                    ),
-                    style::SetForegroundColor(c.color),
-                    style::Print(c.text)
-                )
-                .expect("Std io error.")
-            });
-        });
+    ///
+    /// Every line is printed from its first column in runs of the same color,
+    /// narrow glyphs are followed by a space to fill cjk cells, which are two columns wide
+    pub fn print(&self, w: &mut impl Write, cfg: &Config) {
+        for (y, line) in self.screen.iter().enumerate() {
+            queue!(w, MoveTo(0, y as u16)).expect("Std io error.");
+            let mut run = String::new();
+            let mut color = None;
+            let mut covered = false;
+            for c in line {
+                // The right half of a double-width glyph is already printed
+                if covered {
+                    covered = false;
+                    continue;
+                }
+                let text = if c.text == WIDE_TAIL { ' ' } else { c.text };
+                let width = char_width(text);
+                covered = !cfg.enable_cjk && width == 2;
+                // Spaces show no color, so they don't break a run
+                if text != ' ' && color != Some(c.color) {
+                    if let Some(color) = color {
+                        queue!(w, style::SetForegroundColor(color), style::Print(&run))
+                            .expect("Std io error.");
+                        run.clear();
+                    }
+                    color = Some(c.color);
+                }
+                run.push(text);
+                if cfg.enable_cjk && width == 1 {
+                    run.push(' ');
+                }
+            }
+            queue!(
+                w,
+                style::SetForegroundColor(color.unwrap_or(style::Color::White)),
+                style::Print(&run)
+            )
+            .expect("Std io error.");
+        }
         w.flush().expect("Std io error.");
     }
 
     /// Write the rendering data of all `Fireworks` and `Particles` to `Terminal`
+    ///
+    /// `TextOverlay`s in `TextLayer::Front` are drawn before the `Particles` so that they are not covered,
+    /// `TextOverlay`s in `TextLayer::Back` are drawn after them and only fill the empty cells,
+    /// smoke fills the empty cells behind them, and the background of `Config::scene` fills the cells left at last
     pub fn render(&mut self, fm: &FireworkManager, cfg: &Config) {
         self.clear_screen();
-        for firework in fm.fireworks.iter().rev() {
-            if firework.state == FireworkState::Alive {
-                for particle in firework.current_particles.iter().rev() {
-                    let grad = if firework.config.enable_gradient {
-                        Some((firework.config.gradient_scale)(
-                            particle.time_elapsed.as_secs_f32()
-                                / particle.config.life_time.as_secs_f32(),
-                        ))
+        fm.texts
+            .iter()
+            .filter(|t| t.layer == TextLayer::Front)
+            .for_each(|t| self.render_text(t, cfg));
+        self.render_fireworks(fm, cfg);
+        fm.texts
+            .iter()
+            .filter(|t| t.layer == TextLayer::Back)
+            .for_each(|t| self.render_text(t, cfg));
+        self.render_smoke(fm, cfg);
+        self.render_scene(fm, cfg);
+    }
+
+    /// Fill the empty cells with the smoke of all `Firework`s, which is lit by the bright `Particle`s nearby
+    fn render_smoke(&mut self, fm: &FireworkManager, cfg: &Config) {
+        const BUCKET: (usize, usize) = (8, 4);
+        let (width, height) = (self.size.0 as usize, self.size.1 as usize);
+        let alive = fm
+            .fireworks
+            .iter()
+            .filter(|f| f.state == FireworkState::Alive);
+        if alive.clone().all(|f| f.smoke.is_empty()) {
+            return;
+        }
+        let pivot = self.pivot(cfg);
+        let to_screen =
+            |p: Particle| to_screen(p.pos.extend(p.z), pivot, cfg, fm.time_elapsed).map(round);
+        // Light of the bursts gathered in coarse buckets of the screen
+        let (bw, bh) = (width / BUCKET.0 + 1, height / BUCKET.1 + 1);
+        let mut light = vec![Vec3::ZERO; bw * bh];
+        for (f, p) in alive
+            .clone()
+            .flat_map(|f| f.current_particles.iter().map(move |p| (f, p)))
+        {
+            let brightness = match p.life_state {
+                LifeState::Alive => 1.,
+                LifeState::Declining => 0.5,
+                LifeState::Dying | LifeState::Dead => continue,
+            } * f
+                .config
+                .life_curve_of(&p.config)
+                .intensity(p.life_fraction());
+            let Some((x, y)) = to_screen(p).filter(|p| self.inside(*p)) else {
+                continue;
+            };
+            let (r, g, b) = p.config.color_at(p.life_fraction());
+            light[y as usize / BUCKET.1 * bw + x as usize / BUCKET.0] +=
+                Vec3::new(r as f32, g as f32, b as f32) / 255. * brightness;
+        }
+        // A puff covers a square
+        let aspect = cfg.cell_aspect() as isize;
+        for firework in alive {
+            for p in firework.smoke.iter() {
+                let Some((x, y)) = to_screen(p) else {
+                    continue;
+                };
+                let age = p.life_fraction();
+                let text = if age < 0.5 { '▒' } else { '░' };
+                let fade = 1. - 0.6 * age;
+                let (r, g, b) = p.config.color;
+                for x in x..x + aspect {
+                    if !self.inside((x, y)) || self.screen[y as usize][x as usize].text != ' ' {
+                        continue;
+                    }
+                    let l = light[y as usize / BUCKET.1 * bw + x as usize / BUCKET.0];
+                    // Lit smoke takes the color of the bursts, thin smoke fades into the dark
+                    let t = (l.max_element() / 8.).min(0.6);
+                    let tint = if l.max_element() > 0. {
+                        l / l.max_element() * 255.
                     } else {
-                        None
+                        Vec3::ZERO
                     };
-                    particle
-                        .trail
-                        .iter()
-                        .map(|p| {
-                            if cfg.enable_cjk {
-                                *p
-                            } else {
-                                Vec2::new(p.x * 2., p.y)
-                            }
-                        })
-                        .rev()
-                        .collect::<Vec<_>>()
-                        .windows(2)
-                        .enumerate()
-                        .for_each(|(idx, v)| {
-                            let density = (particle.config.trail_length - idx - 1) as f32
-                                / particle.config.trail_length as f32;
-                            construct_line(v[0], v[1]).iter().for_each(|p| {
-                                if self.inside(*p)
-                                    && self.screen[p.1 as usize][p.0 as usize].text == ' '
-                                {
-                                    if let Some(c) = match particle.life_state {
-                                        LifeState::Alive => {
-                                            Some(get_char_alive(density, cfg.enable_cjk))
-                                        }
-                                        LifeState::Declining => {
-                                            Some(get_char_declining(density, cfg.enable_cjk))
-                                        }
-                                        LifeState::Dying => {
-                                            Some(get_char_dying(density, cfg.enable_cjk))
-                                        }
-                                        LifeState::Dead => None,
-                                    } {
-                                        self.screen[p.1 as usize][p.0 as usize] = Char {
-                                            text: c,
-                                            color: {
-                                                let color_u8 = if let Some(g) = grad {
-                                                    shift_gradient(particle.config.color, g)
-                                                } else {
-                                                    particle.config.color
-                                                };
-                                                style::Color::Rgb {
-                                                    r: color_u8.0,
-                                                    g: color_u8.1,
-                                                    b: color_u8.2,
-                                                }
-                                            },
-                                        }
-                                    }
-                                }
-                            });
-                        });
+                    let mix = |c: u8, l: f32| ((c as f32 + (l - c as f32) * t) * fade) as u8;
+                    let color = style::Color::Rgb {
+                        r: mix(r, tint.x),
+                        g: mix(g, tint.y),
+                        b: mix(b, tint.z),
+                    };
+                    self.screen[y as usize][x as usize] = Char::new(text, color);
                 }
             }
         }
     }
 
+    /// Fill the empty cells with the background of `Config::scene`
+    fn render_scene(&mut self, fm: &FireworkManager, cfg: &Config) {
+        if cfg.scene == Scene::Blank {
+            self.scenery = None;
+            return;
+        }
+        let size = (self.size.0 as usize, self.size.1 as usize);
+        if self
+            .scenery
+            .as_ref()
+            .map_or(true, |s| s.scene != cfg.scene || s.size != size)
+        {
+            self.scenery = Some(Scenery::new(cfg.scene, size.0, size.1, cfg.cell_width(), 0));
+        }
+        let pivot = self.pivot(cfg);
+        let Some(scenery) = self.scenery.as_mut() else {
+            return;
+        };
+        scenery.update_light(fm, |p| to_screen(p, pivot, cfg, fm.time_elapsed));
+        for (y, row) in self.screen.iter_mut().enumerate().take(size.1) {
+            for (x, c) in row.iter_mut().enumerate().take(size.0) {
+                if c.text != ' ' {
+                    continue;
+                }
+                if let Some((text, (r, g, b))) = scenery.cell(x, y, fm.time_elapsed) {
+                    *c = Char::new(text, style::Color::Rgb { r, g, b });
+                }
+            }
+        }
+    }
+
+    /// Mirror the screen above the horizon of `reflection` into the rows below it
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - Simulation time which moves the ripples, e.g. `FireworkManager::time_elapsed`
+    pub fn render_reflection(&mut self, reflection: &Reflection, time: Duration) {
+        let height = self.screen.len();
+        let horizon = ((height as f32 * reflection.horizon).round() as usize).min(height);
+        let depth = (height - horizon).max(1) as f32;
+        let t = time.as_secs_f32() * reflection.ripple_speed;
+        for y in horizon..height {
+            let Some(src_y) = (2 * horizon).checked_sub(y + 1) else {
+                self.screen[y].fill(Char::new(' ', style::Color::White));
+                continue;
+            };
+            let d = (y - horizon) as f32 / depth;
+            // Two waves of different lengths so the ripples don't look regular
+            let wave =
+                0.6 * (y as f32 * 0.9 - t).sin() + 0.4 * (y as f32 * 0.37 + t * 0.65 + 1.7).sin();
+            let shift = (wave * reflection.ripple * (0.5 + d)).round() as isize;
+            let brightness = reflection.brightness * (1. - 0.4 * d);
+            let width = self.screen[y].len();
+            let row = (0..width)
+                .map(|x| {
+                    let src_x = x as isize - shift;
+                    let c = usize::try_from(src_x)
+                        .ok()
+                        .and_then(|sx| self.screen[src_y].get(sx))
+                        .copied()
+                        .unwrap_or(Char::new(' ', style::Color::White));
+                    match c.color {
+                        style::Color::Rgb { r, g, b } if c.text != ' ' => {
+                            let (r, g, b) = shift_gradient((r, g, b), brightness);
+                            Char::new(c.text, style::Color::Rgb { r, g, b })
+                        }
+                        _ => c,
+                    }
+                })
+                .collect::<Vec<_>>();
+            self.screen[y] = row;
+        }
+    }
+
+    /// Draw a bitmap with characters from `Config::glyphs`
+    ///
+    /// # Arguments
+    ///
+    /// * `bitmap` - Lit pixels indexed as `bitmap[row][column]`, see `font::text_bitmap`
+    /// * `center` - Position of the center of the bitmap in terminal cells
+    /// * `px_size` - Width and height of a pixel in terminal cells
+    /// * `color` - Color in RGB (from 0 to 255)
+    /// * `density` - A float between 0 and 1 choosing how dense the characters are, see `GlyphPalette::get_char`
+    /// * `overwrite` - Whether to overwrite the cells which are not empty
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_bitmap(
+        &mut self,
+        bitmap: &[Vec<bool>],
+        center: (isize, isize),
+        px_size: (usize, usize),
+        color: (u8, u8, u8),
+        density: f32,
+        overwrite: bool,
+        cfg: &Config,
+    ) {
+        let Some(bw) = bitmap.first().map(|r| r.len()) else {
+            return;
+        };
+        let (px_w, px_h) = (px_size.0.max(1), px_size.1.max(1));
+        let (w, h) = (bw * px_w, bitmap.len() * px_h);
+        let x0 = center.0 - w as isize / 2;
+        let y0 = center.1 - h as isize / 2;
+        for y in 0..h {
+            for x in 0..w {
+                let p = (x0 + x as isize, y0 + y as isize);
+                if !bitmap[y / px_h][x / px_w]
+                    || !self.inside(p)
+                    || (!overwrite && self.screen[p.1 as usize][p.0 as usize].text != ' ')
+                {
+                    continue;
+                }
+                let mut rng = thread_rng();
+                let jitter = rng.gen_range(0.8..1.);
+                if let Some(c) = cfg
+                    .glyphs()
+                    .get_char(LifeState::Alive, density * jitter, &mut rng)
+                {
+                    let (r, g, b) = color;
+                    self.put(p, Char::new(c, style::Color::Rgb { r, g, b }), cfg);
+                }
+            }
+        }
+    }
+
+    fn render_text(&mut self, text: &TextOverlay, cfg: &Config) {
+        let opacity = text.opacity();
+        if opacity <= 0. {
+            return;
+        }
+        let bitmap = text.bitmap();
+        let Some(bw) = bitmap.first().map(|r| r.len()) else {
+            return;
+        };
+        let (tw, th) = (self.size.0 as usize, self.size.1 as usize);
+        // Pixels are square unless the text doesn't fit
+        let aspect = if bw * cfg.cell_aspect() > tw {
+            1
+        } else {
+            cfg.cell_aspect()
+        };
+        let scale = text
+            .scale
+            .min(tw / (bw * aspect))
+            .min(th / bitmap.len())
+            .max(1);
+        let half_h = (bitmap.len() * scale / 2) as isize;
+        let center = match text.position {
+            TextPosition::Top => ((tw / 2) as isize, 1 + half_h),
+            TextPosition::Center => ((tw / 2) as isize, (th / 2) as isize),
+            TextPosition::Bottom => ((tw / 2) as isize, th as isize - 2 - half_h),
+            TextPosition::At(p) => {
+                if cfg.enable_cjk {
+                    round(p)
+                } else {
+                    round(Vec2::new(p.x * 2., p.y))
+                }
+            }
+        };
+        self.draw_bitmap(
+            &bitmap,
+            center,
+            (scale * aspect, scale),
+            shift_gradient(text.color, opacity),
+            opacity,
+            text.layer == TextLayer::Front,
+            cfg,
+        );
+    }
+
+    fn render_fireworks(&mut self, fm: &FireworkManager, cfg: &Config) {
+        let pivot = self.pivot(cfg);
+        // Particles in drawing order, the first drawn ones cover the later ones
+        let mut order = fm
+            .fireworks
+            .iter()
+            .rev()
+            .filter(|f| f.state == FireworkState::Alive)
+            .flat_map(|f| {
+                let store = &f.current_particles;
+                store.indices().rev().filter_map(move |i| {
+                    let particle = store.get(i)?;
+                    let depth = match &cfg.camera {
+                        Some(camera) => {
+                            camera
+                                .project(particle.pos.extend(particle.z), pivot, fm.time_elapsed)?
+                                .1
+                        }
+                        None => 0.,
+                    };
+                    Some((f, i, particle, depth))
+                })
+            })
+            .collect::<Vec<_>>();
+        if cfg.camera.is_some() {
+            // Nearer particles are drawn first so that they cover farther ones
+            order.sort_by(|a, b| a.3.total_cmp(&b.3));
+        }
+        let mut rng = thread_rng();
+        for (firework, i, particle, depth) in order {
+            let store = &firework.current_particles;
+            let glyphs = firework.config.glyphs.as_ref().unwrap_or(cfg.glyphs());
+            let effect = particle.config.effect;
+            if !effect.is_visible(i, particle.time_elapsed) {
+                continue;
+            }
+            let glitter = matches!(effect, Effect::Glitter { .. });
+            let life_fraction =
+                particle.time_elapsed.as_secs_f32() / particle.config.life_time.as_secs_f32();
+            let intensity = firework
+                .config
+                .life_curve_of(&particle.config)
+                .intensity(life_fraction);
+            let mut color_u8 = if firework.config.enable_gradient {
+                shift_gradient(
+                    particle.config.color_at(life_fraction),
+                    (firework.config.gradient_scale)(life_fraction),
+                )
+            } else {
+                particle.config.color_at(life_fraction)
+            };
+            if intensity < 1. {
+                color_u8 = shift_gradient(color_u8, intensity);
+            }
+            if let Some(camera) = &cfg.camera {
+                color_u8 = shift_gradient(color_u8, camera.brightness(depth));
+            }
+            let color = style::Color::Rgb {
+                r: color_u8.0,
+                g: color_u8.1,
+                b: color_u8.2,
+            };
+            store
+                .trail_3d(i)
+                .filter_map(|p| to_screen(p, pivot, cfg, fm.time_elapsed))
+                .rev()
+                .collect::<Vec<_>>()
+                .windows(2)
+                .enumerate()
+                .for_each(|(idx, v)| {
+                    let density = (particle.config.trail_length - idx - 1) as f32
+                        / particle.config.trail_length as f32
+                        * intensity;
+                    construct_line(v[0], v[1]).iter().for_each(|p| {
+                        if !self.inside(*p) || self.screen[p.1 as usize][p.0 as usize].text != ' ' {
+                            return;
+                        }
+                        // Behind its head, a glittering trail is only a few sparkles
+                        let c = if glitter && idx > 0 {
+                            if !effect.sparkles(&mut rng) {
+                                return;
+                            }
+                            glyphs.get_sparkle(&mut rng)
+                        } else {
+                            glyphs.get_char(particle.life_state, density, &mut rng)
+                        };
+                        if let Some(c) = c {
+                            let color = if glitter && idx > 0 {
+                                let (r, g, b) = shift_gradient(color_u8, 1.4);
+                                style::Color::Rgb { r, g, b }
+                            } else {
+                                color
+                            };
+                            self.put(*p, Char::new(c, color), cfg);
+                        }
+                    });
+                });
+        }
+    }
+
+    /// Return the center of the screen in simulation units, which the `Camera` orbits around
+    fn pivot(&self, cfg: &Config) -> Vec2 {
+        let width = if cfg.enable_cjk {
+            self.size.0 as f32
+        } else {
+            self.size.0 as f32 / 2.
+        };
+        Vec2::new(width / 2., self.size.1 as f32 / 2.)
+    }
+
+    /// Put `c` in the cell at `p`, a double-width glyph also covers the next cell unless cjk cells are two columns wide
+    ///
+    /// A cell covered by a double-width glyph on its left keeps it, and a double-width glyph in the last column doesn't
+    /// fit, in both cases `c` is not put
+    fn put(&mut self, (x, y): (isize, isize), c: Char, cfg: &Config) {
+        if self.screen[y as usize][x as usize].text == WIDE_TAIL {
+            return;
+        }
+        if !cfg.enable_cjk && char_width(c.text) == 2 {
+            if !self.inside((x + 1, y)) {
+                return;
+            }
+            self.screen[y as usize][x as usize + 1] = Char::new(WIDE_TAIL, c.color);
+        }
+        self.screen[y as usize][x as usize] = c;
+    }
+
     fn inside(&self, (x, y): (isize, isize)) -> bool {
         x < self.size.0 as isize && y < self.size.1 as isize && x >= 0 && y >= 0
     }
 }
 
+/// Return the position on the screen in columns and rows of a point in the simulation,
+/// projected by `Config::camera` around `pivot` if any, or `None` if it is behind the camera
+fn to_screen(p: Vec3, pivot: Vec2, cfg: &Config, time: Duration) -> Option<Vec2> {
+    let p = match &cfg.camera {
+        Some(camera) => camera.project(p, pivot, time)?.0,
+        None => p.truncate(),
+    };
+    Some(if cfg.enable_cjk {
+        p
+    } else {
+        Vec2::new(p.x * 2., p.y)
+    })
+}
+
 fn construct_line(a: Vec2, b: Vec2) -> Vec<(isize, isize)> {
     const STEP: f32 = 0.2;
     let (x0, y0) = (a.x, a.y);
@@ -257,87 +653,18 @@ fn shift_gradient(color: (u8, u8, u8), scale: f32) -> (u8, u8, u8) {
     )
 }
 
-fn get_char_alive(density: f32, cjk: bool) -> char {
-    let palette = if density < 0.3 {
-        if cjk {
-            "。，”“』 『￥"
-        } else {
-            "`'. "
-        }
-    } else if density < 0.5 {
-        if cjk {
-            "一二三二三五十十已于上下义天"
-            // "いうよへくひとフーク "
-        } else {
-            "/\\|()1{}[]?"
-        }
-    } else if density < 0.7 {
-        if cjk {
-            "时中自字木月日目火田左右点以"
-            // "探しているのが誰かなのかどこかなのかそれともただ単に就職先なのか自分でもよくわからない"
-        } else {
-            "oahkbdpqwmZO0QLCJUYXzcvunxrjft*"
-        }
-    } else if cjk {
-        "龖龠龜"
-        // "東京福岡横浜縄"
-    } else {
-        "$@B%8&WM#"
-    };
-    palette
-        .chars()
-        .choose(&mut thread_rng())
-        .expect("Fail to choose character.")
-}
-
-fn get_char_declining(density: f32, cjk: bool) -> char {
-    let palette = if density < 0.2 {
-        if cjk {
-            "？。， 『』 ||"
-        } else {
-            "` '. "
-        }
-    } else if density < 0.6 {
-        if cjk {
-            "（）【】*￥|十一二三六"
-            // "（）【】*￥|ソファー"
-        } else {
-            "-_ +~<> i!lI;:,\"^"
-        }
-    } else if density < 0.85 {
-        if cjk {
-            "人中亿入上下火土"
-            // "人ならざるものに出会うかもしれない"
-        } else {
-            "/\\| ()1{}[ ]?"
-        }
-    } else if cjk {
-        "繁荣昌盛国泰民安龍龖龠龜耋"
-        // "時間言葉目覚"
-    } else {
-        "xrjft*"
-    };
-    palette
-        .chars()
-        .choose(&mut thread_rng())
-        .expect("Fail to choose character.")
-}
-
-fn get_char_dying(density: f32, cjk: bool) -> char {
-    let palette = if density < 0.6 {
-        if cjk {
-            "。 『 』 、： |。，— ……"
-        } else {
-            ".  ,`.    ^,' . "
-        }
-    } else if cjk {
-        "|￥人 上十入乙小 下"
-        // "イントマトナイフ"
+/// Pick a random character of a `LifeState` from the built-in ascii or cjk `GlyphPalette`
+///
+/// # Arguments
+///
+/// * `life_state` - Each `LifeState` has its own palette, `Dead` returns `None`
+/// * `density` - A float between 0 and 1, larger `density` picks denser characters
+/// * `cjk` - Whether to pick from the cjk palette
+pub fn get_char(life_state: LifeState, density: f32, cjk: bool) -> Option<char> {
+    let glyphs = if cjk {
+        GlyphPalette::CJK
     } else {
-        " /\\| ( )  1{} [  ]?i !l I;: ,\"^ "
+        GlyphPalette::ASCII
     };
-    palette
-        .chars()
-        .choose(&mut thread_rng())
-        .expect("Fail to choose character.")
+    glyphs.get_char(life_state, density, &mut thread_rng())
 }
@@ -0,0 +1,176 @@
+//! `text` module provides text overlays drawn in large letters together with the fireworks
+
+use std::time::Duration;
+
+use glam::Vec2;
+
+use crate::font::text_bitmap;
+
+/// Struct representing a message drawn in large letters with the built-in bitmap font
+///
+/// A `TextOverlay` waits for `spawn_after`, fades in, stays for `hold` and fades out
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextOverlay {
+    pub text: String,
+    pub position: TextPosition,
+    /// Color in RGB (from 0 to 255)
+    pub color: (u8, u8, u8),
+    pub layer: TextLayer,
+    /// Size of a pixel of the font in terminal rows
+    ///
+    /// The text is shrunk if it doesn't fit in the terminal
+    pub scale: usize,
+    /// Text shows after `spawn_after` since it is added
+    pub spawn_after: Duration,
+    pub fade_in: Duration,
+    /// `Duration` that the text stays at full brightness, `None` means it never fades out
+    pub hold: Option<Duration>,
+    pub fade_out: Duration,
+    pub time_elapsed: Duration,
+}
+
+impl Default for TextOverlay {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            position: TextPosition::Center,
+            color: (255, 255, 255),
+            layer: TextLayer::Front,
+            scale: 1,
+            spawn_after: Duration::ZERO,
+            fade_in: Duration::from_secs(1),
+            hold: None,
+            fade_out: Duration::from_secs(1),
+            time_elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl TextOverlay {
+    /// Create a new `TextOverlay` at the center of the terminal
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set `position`
+    #[inline]
+    #[must_use]
+    pub fn with_position(mut self, position: TextPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Set `color`
+    #[inline]
+    #[must_use]
+    pub fn with_color(mut self, color: (u8, u8, u8)) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set `layer`
+    #[inline]
+    #[must_use]
+    pub fn with_layer(mut self, layer: TextLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    /// Set `scale`
+    #[inline]
+    #[must_use]
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Set `spawn_after`
+    #[inline]
+    #[must_use]
+    pub fn with_spawn_after(mut self, spawn_after: Duration) -> Self {
+        self.spawn_after = spawn_after;
+        self
+    }
+
+    /// Set `fade_in`, `hold` and `fade_out`
+    #[inline]
+    #[must_use]
+    pub fn with_timing(
+        mut self,
+        fade_in: Duration,
+        hold: Option<Duration>,
+        fade_out: Duration,
+    ) -> Self {
+        self.fade_in = fade_in;
+        self.hold = hold;
+        self.fade_out = fade_out;
+        self
+    }
+
+    /// Update the `TextOverlay` based on delta time
+    pub fn update(&mut self, delta_time: Duration) {
+        self.time_elapsed += delta_time;
+    }
+
+    /// Reset `TextOverlay` to its initial state
+    pub fn reset(&mut self) {
+        self.time_elapsed = Duration::ZERO;
+    }
+
+    /// Return true if the text has faded out
+    pub fn is_gone(&self) -> bool {
+        match self.hold {
+            Some(hold) => {
+                self.time_elapsed >= self.spawn_after + self.fade_in + hold + self.fade_out
+            }
+            None => false,
+        }
+    }
+
+    /// Return the current brightness of the text, from 0 (invisible) to 1
+    pub fn opacity(&self) -> f32 {
+        let Some(t) = self.time_elapsed.checked_sub(self.spawn_after) else {
+            return 0.;
+        };
+        if t < self.fade_in {
+            return t.as_secs_f32() / self.fade_in.as_secs_f32();
+        }
+        let Some(hold) = self.hold else {
+            return 1.;
+        };
+        let t = t - self.fade_in;
+        if t < hold {
+            1.
+        } else if t < hold + self.fade_out {
+            1. - (t - hold).as_secs_f32() / self.fade_out.as_secs_f32()
+        } else {
+            0.
+        }
+    }
+
+    /// Return the bitmap of the text, indexed as `bitmap[row][column]`
+    pub fn bitmap(&self) -> Vec<Vec<bool>> {
+        text_bitmap(&self.text, 1)
+    }
+}
+
+/// Where a `TextOverlay` is placed in the terminal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextPosition {
+    Top,
+    Center,
+    Bottom,
+    /// Center of the text in the same coordinates as `Firework::center`
+    At(Vec2),
+}
+
+/// Whether a `TextOverlay` is drawn in front of or behind the `Particle`s
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextLayer {
+    #[default]
+    Front,
+    Back,
+}
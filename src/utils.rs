@@ -1,11 +1,26 @@
 //! `utils` module provides some useful helper functions of random generation and gradient scale
+//!
+//! Functions ending with `_with_rng` draw their randomness from the given `rng`, the same `rng` state gives the same
+//! points
 
 use std::f32::consts::PI;
 
-use glam::Vec2;
+use glam::{Quat, Vec2, Vec3};
 use rand::Rng;
 use rand_distr::Distribution;
 
+use crate::{font::text_bitmap, image::Image, particle::ForceContext};
+
+/// Angle between two neighbouring points of a golden-angle spiral, in radians
+const GOLDEN_ANGLE: f32 = 2.399_963;
+
+/// Return how many cells `cell_width` terminal columns wide are as wide as a cell is high
+///
+/// Terminal cells are about twice as high as they are wide, so a square is two ascii cells or one cjk cell
+pub fn cell_aspect(cell_width: usize) -> usize {
+    (2 / cell_width.max(1)).max(1)
+}
+
 /// Round a `Vec2` from `(f32, f32)` to `(isize, isize)`
 pub fn round(input: Vec2) -> (isize, isize) {
     (input.x.round() as isize, input.y.round() as isize)
@@ -13,39 +28,34 @@ pub fn round(input: Vec2) -> (isize, isize) {
 
 /// Generate random `Vec2` within a circle range
 pub fn gen_points_circle(radius: isize, n: usize) -> Vec<Vec2> {
-    let mut res = Vec::new();
-    while res.len() < n {
-        let x = rand::thread_rng().gen_range(-radius..=radius);
-        let y = rand::thread_rng().gen_range(-radius..=radius);
-        if x.pow(2) + y.pow(2) <= radius.pow(2) {
-            res.push(Vec2::new(x as f32, y as f32));
-        }
-    }
-    res
+    gen_points_circle_with_rng(radius, n, &mut rand::thread_rng())
+}
+
+/// Generate random `Vec2` within a circle range with `rng`
+///
+/// Points have integer coordinates, a negative `radius` gives points at the center
+pub fn gen_points_circle_with_rng(radius: isize, n: usize, rng: &mut impl Rng) -> Vec<Vec2> {
+    let radius = radius.max(0) as f32;
+    (0..n)
+        .map(|_| {
+            // Uniform over the area, truncating towards the center keeps the points inside
+            let r = radius * rng.gen_range(0f32..=1.).sqrt();
+            let a = rng.gen_range(0. ..2. * PI);
+            Vec2::new((r * a.cos()).trunc(), (r * a.sin()).trunc())
+        })
+        .collect()
 }
 
 /// Generate random `Vec2` within a circle range with normal distribution
 ///
 /// Points closer to the center will be denser
 pub fn gen_points_circle_normal(radius: f32, n: usize) -> Vec<Vec2> {
-    let mut rng = rand::thread_rng();
-    let normal =
-        rand_distr::Normal::new(0., radius / 9.).expect("Unable to generate normal distribution.");
-    let mut res = Vec::new();
-    while res.len() < n {
-        let x = normal.sample(&mut rng);
-        if x < -radius || x > radius {
-            continue;
-        }
-        let y = normal.sample(&mut rng);
-        if x < -radius || y > radius {
-            continue;
-        }
-        if x.powi(2) + y.powi(2) <= radius.powi(2) {
-            res.push(Vec2::new(x, y));
-        }
-    }
-    res
+    gen_points_circle_normal_dev(radius, n, radius / 9.)
+}
+
+/// Generate random `Vec2` within a circle range with normal distribution with `rng`
+pub fn gen_points_circle_normal_with_rng(radius: f32, n: usize, rng: &mut impl Rng) -> Vec<Vec2> {
+    gen_points_circle_normal_dev_with_rng(radius, n, radius / 9., rng)
 }
 
 /// Generate random `Vec2` within a circle range with normal distribution
@@ -53,17 +63,40 @@ pub fn gen_points_circle_normal(radius: f32, n: usize) -> Vec<Vec2> {
 /// Points closer to the center will be denser
 /// You can specify standard deviation yourself
 pub fn gen_points_circle_normal_dev(radius: f32, n: usize, std_dev: f32) -> Vec<Vec2> {
-    let mut rng = rand::thread_rng();
+    gen_points_circle_normal_dev_with_rng(radius, n, std_dev, &mut rand::thread_rng())
+}
+
+/// Generate random `Vec2` within a circle range with normal distribution of `std_dev` with `rng`
+///
+/// # Notes
+///
+/// - All the points are at the center if `radius` is not positive, as with `gen_points_circle`
+/// - `std_dev` is clamped between 0 and `4 * radius`, wider distributions are almost uniform in the circle anyway,
+///   and a NaN `std_dev` is taken as 0
+pub fn gen_points_circle_normal_dev_with_rng(
+    radius: f32,
+    n: usize,
+    std_dev: f32,
+    rng: &mut impl Rng,
+) -> Vec<Vec2> {
+    if n == 0 || radius.is_nan() || radius <= 0. {
+        return vec![Vec2::ZERO; n];
+    }
+    let std_dev = if std_dev.is_nan() {
+        0.
+    } else {
+        std_dev.clamp(0., 4. * radius)
+    };
     let normal =
         rand_distr::Normal::new(0., std_dev).expect("Unable to generate normal distribution.");
-    let mut res = Vec::new();
+    let mut res = Vec::with_capacity(n);
     while res.len() < n {
-        let x = normal.sample(&mut rng);
+        let x = normal.sample(rng);
         if x < -radius || x > radius {
             continue;
         }
-        let y = normal.sample(&mut rng);
-        if x < -radius || y > radius {
+        let y = normal.sample(rng);
+        if y < -radius || y > radius {
             continue;
         }
         if x.powi(2) + y.powi(2) <= radius.powi(2) {
@@ -75,38 +108,604 @@ pub fn gen_points_circle_normal_dev(radius: f32, n: usize, std_dev: f32) -> Vec<
 
 /// Generate random `Vec2` within a fan-shape range
 pub fn gen_points_fan(radius: f32, n: usize, st_angle: f32, ed_angle: f32) -> Vec<Vec2> {
-    let mut res = Vec::new();
-    while res.len() < n {
-        let x = rand::thread_rng().gen_range(-radius..=radius);
-        let y = rand::thread_rng().gen_range(-radius..=radius);
-        let t = y.atan2(x);
-        if t <= ed_angle && t >= st_angle && x.powi(2) + y.powi(2) <= radius.powi(2) {
-            res.push(Vec2::new(x, -y));
+    gen_points_fan_with_rng(radius, n, st_angle, ed_angle, &mut rand::thread_rng())
+}
+
+/// Generate random `Vec2` within a fan-shape range with `rng`
+///
+/// The fan goes counterclockwise from the right, between `st_angle` and `ed_angle` given in either order
+pub fn gen_points_fan_with_rng(
+    radius: f32,
+    n: usize,
+    st_angle: f32,
+    ed_angle: f32,
+    rng: &mut impl Rng,
+) -> Vec<Vec2> {
+    let (st_angle, ed_angle) = (st_angle.min(ed_angle), st_angle.max(ed_angle));
+    (0..n)
+        .map(|_| {
+            let r = radius * rng.gen_range(0f32..=1.).sqrt();
+            let a = rng.gen_range(st_angle..=ed_angle);
+            Vec2::new(r * a.cos(), -r * a.sin())
+        })
+        .collect()
+}
+
+/// Generate random `Vec2` on an arc
+pub fn gen_points_arc(radius: f32, n: usize, st_angle: f32, ed_angle: f32) -> Vec<Vec2> {
+    gen_points_arc_with_rng(radius, n, st_angle, ed_angle, &mut rand::thread_rng())
+}
+
+/// Generate random `Vec2` on an arc with `rng`
+pub fn gen_points_arc_with_rng(
+    radius: f32,
+    n: usize,
+    st_angle: f32,
+    ed_angle: f32,
+    rng: &mut impl Rng,
+) -> Vec<Vec2> {
+    (0..n)
+        .map(|_| {
+            let a = rng.gen_range(st_angle..=ed_angle);
+            Vec2::new(radius * a.cos(), -radius * a.sin())
+        })
+        .collect()
+}
+
+/// Generate random `Vec2` on a circle
+pub fn gen_points_on_circle(radius: f32, n: usize) -> Vec<Vec2> {
+    gen_points_on_circle_with_rng(radius, n, &mut rand::thread_rng())
+}
+
+/// Generate random `Vec2` on a circle with `rng`
+pub fn gen_points_on_circle_with_rng(radius: f32, n: usize, rng: &mut impl Rng) -> Vec<Vec2> {
+    gen_points_arc_with_rng(radius, n, 0., 2. * PI, rng)
+}
+
+/// Generate `n` `Vec2` evenly spaced on a circle, the first one at `offset` radians counterclockwise from the right
+pub fn gen_points_on_circle_even(radius: f32, n: usize, offset: f32) -> Vec<Vec2> {
+    (0..n)
+        .map(|i| {
+            let a = offset + 2. * PI * i as f32 / n as f32;
+            Vec2::new(radius * a.cos(), -radius * a.sin())
+        })
+        .collect()
+}
+
+/// Generate `n` `Vec2` evenly filling a circle along a golden-angle (Fibonacci) spiral
+///
+/// The pattern is the same every time, and looks like the seeds of a sunflower
+pub fn gen_points_fibonacci_disk(radius: f32, n: usize) -> Vec<Vec2> {
+    (0..n)
+        .map(|i| {
+            let r = radius * ((i as f32 + 0.5) / n as f32).sqrt();
+            let a = i as f32 * GOLDEN_ANGLE;
+            Vec2::new(r * a.cos(), r * a.sin())
+        })
+        .collect()
+}
+
+/// Generate `n` `Vec3` evenly spread on a sphere along a golden-angle (Fibonacci) spiral
+///
+/// The pattern is the same every time, without the clumps of random points
+pub fn gen_vectors_fibonacci_sphere(radius: f32, n: usize) -> Vec<Vec3> {
+    (0..n)
+        .map(|i| {
+            let z = 1. - 2. * (i as f32 + 0.5) / n as f32;
+            let r = (1. - z * z).sqrt();
+            let a = i as f32 * GOLDEN_ANGLE;
+            Vec3::new(r * a.cos(), r * a.sin(), z) * radius
+        })
+        .collect()
+}
+
+/// Generate random `Vec2` within a circle, no two of them closer than `min_distance` (Poisson-disk sampling)
+///
+/// Points are spread evenly without looking regular, the number of points depends on `radius` and `min_distance`
+///
+/// # Notes
+///
+/// - `min_distance` is at least `radius / 90`, which bounds the grid of the sampling to 256 by 256 cells
+pub fn gen_points_poisson_disk(radius: f32, min_distance: f32) -> Vec<Vec2> {
+    gen_points_poisson_disk_with_rng(radius, min_distance, &mut rand::thread_rng())
+}
+
+/// Generate random `Vec2` within a circle, no two of them closer than `min_distance`, with `rng`
+///
+/// See `gen_points_poisson_disk`
+pub fn gen_points_poisson_disk_with_rng(
+    radius: f32,
+    min_distance: f32,
+    rng: &mut impl Rng,
+) -> Vec<Vec2> {
+    // Bridson's algorithm, with a grid of cells that hold at most one point each
+    const ATTEMPTS: usize = 30;
+    // Cells on a side of the grid at most
+    const MAX_SIDE: usize = 256;
+    if radius <= 0. || min_distance <= 0. || !radius.is_finite() {
+        return vec![Vec2::ZERO];
+    }
+    let min_distance = min_distance.max(2. * radius * 2f32.sqrt() / (MAX_SIDE - 1) as f32);
+    let cell = min_distance / 2f32.sqrt();
+    let side = ((2. * radius / cell).ceil() as usize + 1).min(MAX_SIDE);
+    let mut grid: Vec<Option<usize>> = vec![None; side * side];
+    let to_cell = |p: Vec2| {
+        let c = ((p + radius) / cell).floor();
+        (c.x as usize).min(side - 1) + (c.y as usize).min(side - 1) * side
+    };
+    let mut res = vec![Vec2::ZERO];
+    grid[to_cell(Vec2::ZERO)] = Some(0);
+    let mut active = vec![0];
+    while !active.is_empty() {
+        let k = rng.gen_range(0..active.len());
+        let center = res[active[k]];
+        let found = (0..ATTEMPTS).find_map(|_| {
+            let a = rng.gen_range(0. ..2. * PI);
+            let d = rng.gen_range(min_distance..2. * min_distance);
+            let p = center + Vec2::new(a.cos(), a.sin()) * d;
+            if p.length() > radius {
+                return None;
+            }
+            let c = ((p + radius) / cell).floor();
+            let (cx, cy) = (c.x as isize, c.y as isize);
+            let far_enough = (cy - 2..=cy + 2)
+                .flat_map(|y| (cx - 2..=cx + 2).map(move |x| (x, y)))
+                .filter(|&(x, y)| x >= 0 && y >= 0 && (x as usize) < side && (y as usize) < side)
+                .filter_map(|(x, y)| grid[x as usize + y as usize * side])
+                .all(|j| res[j].distance_squared(p) >= min_distance * min_distance);
+            far_enough.then_some(p)
+        });
+        match found {
+            Some(p) => {
+                grid[to_cell(p)] = Some(res.len());
+                active.push(res.len());
+                res.push(p);
+            }
+            None => {
+                active.swap_remove(k);
+            }
         }
     }
     res
 }
 
-/// Generate random `Vec2` on an arc
-pub fn gen_points_arc(radius: f32, n: usize, st_angle: f32, ed_angle: f32) -> Vec<Vec2> {
+/// Return the orientation of a 3D shape seen from the front
+///
+/// # Arguments
+///
+/// * `tilt` - Angle in radians the shape leans back around the horizontal axis, `PI / 2.` shows a ring edge-on
+/// * `rotation` - Angle in radians the tilted shape then turns around the axis pointing at the viewer
+pub fn orientation(tilt: f32, rotation: f32) -> Quat {
+    Quat::from_rotation_z(rotation) * Quat::from_rotation_x(tilt)
+}
+
+/// Generate `Vec3` evenly spaced on a ring
+///
+/// The ring faces the viewer before it is turned by `orientation`, see `orientation`
+pub fn gen_vectors_ring(radius: f32, n: usize, orientation: Quat) -> Vec<Vec3> {
+    gen_vectors_ring_with_rng(radius, n, orientation, &mut rand::thread_rng())
+}
+
+/// Generate `Vec3` evenly spaced on a ring, starting at a random angle given by `rng`
+///
+/// See `gen_vectors_ring`
+pub fn gen_vectors_ring_with_rng(
+    radius: f32,
+    n: usize,
+    orientation: Quat,
+    rng: &mut impl Rng,
+) -> Vec<Vec3> {
+    let offset = rng.gen_range(0. ..2. * PI);
+    (0..n)
+        .map(|i| {
+            let a = offset + 2. * PI * i as f32 / n as f32;
+            orientation * Vec3::new(a.cos(), a.sin(), 0.) * radius
+        })
+        .collect()
+}
+
+/// Generate random `Vec3` evenly spread on a sphere
+pub fn gen_vectors_sphere(radius: f32, n: usize) -> Vec<Vec3> {
+    gen_vectors_sphere_with_rng(radius, n, &mut rand::thread_rng())
+}
+
+/// Generate random `Vec3` evenly spread on a sphere with `rng`
+pub fn gen_vectors_sphere_with_rng(radius: f32, n: usize, rng: &mut impl Rng) -> Vec<Vec3> {
+    (0..n)
+        .map(|_| {
+            let z: f32 = rng.gen_range(-1. ..1.);
+            let a = rng.gen_range(0. ..2. * PI);
+            let r = (1. - z * z).sqrt();
+            Vec3::new(r * a.cos(), r * a.sin(), z) * radius
+        })
+        .collect()
+}
+
+/// Generate `Vec2` evenly spaced on a ring in 3D, projected on the screen
+///
+/// The ring faces the viewer before it is turned by `orientation`, see `orientation`
+pub fn gen_points_ring_3d(radius: f32, n: usize, orientation: Quat) -> Vec<Vec2> {
+    gen_vectors_ring(radius, n, orientation)
+        .into_iter()
+        .map(Vec3::truncate)
+        .collect()
+}
+
+/// Generate `Vec2` on two rings crossing at right angles in 3D, projected on the screen
+///
+/// `n` points are on each ring
+pub fn gen_points_double_ring_3d(radius: f32, n: usize, orientation: Quat) -> Vec<Vec2> {
+    let mut res = gen_points_ring_3d(radius, n, orientation);
+    res.extend(gen_points_ring_3d(
+        radius,
+        n,
+        orientation * Quat::from_rotation_y(PI / 2.),
+    ));
+    res
+}
+
+/// Generate random `Vec2` evenly spread on a sphere in 3D, projected on the screen
+///
+/// Projected points are denser towards the edge, like the stars of a spherical shell seen from the side
+pub fn gen_points_sphere_3d(radius: f32, n: usize) -> Vec<Vec2> {
+    gen_vectors_sphere(radius, n)
+        .into_iter()
+        .map(Vec3::truncate)
+        .collect()
+}
+
+/// Generate `Vec2` of a saturn shell, a sphere of `n_sphere` points in a ring of `n_ring` points, projected on the screen
+///
+/// The ring faces the viewer before it is turned by `orientation`, see `orientation`
+pub fn gen_points_saturn_3d(
+    radius: f32,
+    n_sphere: usize,
+    ring_radius: f32,
+    n_ring: usize,
+    orientation: Quat,
+) -> Vec<Vec2> {
+    let mut res = gen_points_sphere_3d(radius, n_sphere);
+    res.extend(gen_points_ring_3d(ring_radius, n_ring, orientation));
+    res
+}
+
+/// Generate `Vec2` on the lit pixels of a text written with the built-in bitmap font
+///
+/// Points are centered at zero, and `pixel_size` is the distance between two neighbouring pixels
+pub fn gen_points_text(text: &str, pixel_size: f32) -> Vec<Vec2> {
+    gen_points_bitmap(&text_bitmap(text, 1), pixel_size)
+}
+
+/// Generate `Vec2` on the lit characters of an ascii mask, where space and `.` are unlit
+pub fn gen_points_mask(mask: &str, pixel_size: f32) -> Vec<Vec2> {
+    let rows = mask
+        .lines()
+        .skip_while(|l| l.trim().is_empty())
+        .map(|l| l.chars().map(|c| c != ' ' && c != '.').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let bitmap = rows
+        .into_iter()
+        .map(|mut r| {
+            r.resize(width, false);
+            r
+        })
+        .collect::<Vec<_>>();
+    gen_points_bitmap(&bitmap, pixel_size)
+}
+
+/// Generate `Vec2` on the lit pixels of a bitmap indexed as `bitmap[row][column]`
+///
+/// Points are centered at zero, and `pixel_size` is the distance between two neighbouring pixels
+pub fn gen_points_bitmap(bitmap: &[Vec<bool>], pixel_size: f32) -> Vec<Vec2> {
+    let h = bitmap.len() as f32;
+    let w = bitmap.iter().map(|r| r.len()).max().unwrap_or(0) as f32;
+    let offset = Vec2::new((w - 1.) / 2., (h - 1.) / 2.);
     let mut res = Vec::new();
-    while res.len() < n {
-        let a = rand::thread_rng().gen_range(st_angle..=ed_angle);
-        res.push(Vec2::new(radius * a.cos(), -radius * a.sin()));
+    for (y, row) in bitmap.iter().enumerate() {
+        for (x, lit) in row.iter().enumerate() {
+            if *lit {
+                res.push((Vec2::new(x as f32, y as f32) - offset) * pixel_size);
+            }
+        }
     }
     res
 }
 
-/// Generate random `Vec2` on a circle
-pub fn gen_points_on_circle(radius: f32, n: usize) -> Vec<Vec2> {
+/// Generate `Vec2` and colors of the pixels of an `Image`, down-sampled to at most `n` points
+///
+/// Points are centered at zero, and `pixel_size` is the distance between two neighbouring points.
+/// Pass the points to `gen_shape_velocities` and the colors to `ParticleConfig::new` to paint the picture
+///
+/// # Notes
+///
+/// - Transparent and black pixels are background and get no point, as the sky is black
+/// - The image is down-sampled by averaging square blocks of pixels, a block gets a point if at least half of it is lit
+/// - The block size is guessed from the number of lit pixels, and grown only if the blocks still give more than `n` points
+pub fn gen_points_image(image: &Image, n: usize, pixel_size: f32) -> Vec<(Vec2, (u8, u8, u8))> {
+    let lit = |(r, g, b, a): (u8, u8, u8, u8)| a >= 128 && r.max(g).max(b) > 16;
+    let lit_pixels = image.pixels.iter().filter(|p| lit(**p)).count();
+    if n == 0 || lit_pixels == 0 {
+        return Vec::new();
+    }
+    // A block of `block * block` pixels makes about one point out of that many lit pixels,
+    // and a block as large as the image makes one point
+    let largest = image.width.max(image.height).max(1);
+    let first = ((lit_pixels as f32 / n as f32).sqrt().ceil() as usize).clamp(1, largest);
     let mut res = Vec::new();
-    while res.len() < n {
-        let a = rand::thread_rng().gen_range(0.0..PI);
-        res.push(Vec2::new(radius * a.cos(), -radius * a.sin()));
+    for block in first..=largest {
+        let (w, h) = (image.width.div_ceil(block), image.height.div_ceil(block));
+        let offset = Vec2::new((w as f32 - 1.) / 2., (h as f32 - 1.) / 2.);
+        res.clear();
+        for by in 0..h {
+            for bx in 0..w {
+                let (mut sum, mut count, mut total) = ([0; 3], 0, 0);
+                for y in by * block..((by + 1) * block).min(image.height) {
+                    for x in bx * block..((bx + 1) * block).min(image.width) {
+                        let p = image.pixel(x, y);
+                        total += 1;
+                        if lit(p) {
+                            sum[0] += p.0 as usize;
+                            sum[1] += p.1 as usize;
+                            sum[2] += p.2 as usize;
+                            count += 1;
+                        }
+                    }
+                }
+                if count * 2 >= total && count > 0 {
+                    let color = (
+                        (sum[0] / count) as u8,
+                        (sum[1] / count) as u8,
+                        (sum[2] / count) as u8,
+                    );
+                    let pos = (Vec2::new(bx as f32, by as f32) - offset) * pixel_size;
+                    res.push((pos, color));
+                }
+            }
+        }
+        if res.len() <= n {
+            break;
+        }
     }
     res
 }
 
+/// Parse the path data of an SVG `<path>` (its `d` attribute) into polylines
+///
+/// Curves are flattened into short segments, a new polyline starts at every move command.
+/// Return `None` if the path is malformed
+///
+/// # Notes
+///
+/// - Supported commands are `M`, `L`, `H`, `V`, `C`, `Q`, `Z` and their relative lowercase forms
+/// - SVG's y axis points down like the terminal's, so the shape is not flipped
+pub fn parse_svg_path(path: &str) -> Option<Vec<Vec<Vec2>>> {
+    // Segments per flattened curve
+    const CURVE_SEGMENTS: usize = 16;
+    let tokens = tokenize_svg_path(path)?;
+    let mut res: Vec<Vec<Vec2>> = Vec::new();
+    let mut current = Vec2::ZERO;
+    let mut start = Vec2::ZERO;
+    let mut i = 0;
+    let mut command = None;
+    while i < tokens.len() {
+        let cmd = match tokens[i] {
+            SvgToken::Command(c) => {
+                i += 1;
+                c
+            }
+            // Repeated arguments repeat the previous command, and a move is followed by lines
+            SvgToken::Number(_) => match command? {
+                'M' => 'L',
+                'm' => 'l',
+                'Z' | 'z' => return None,
+                c => c,
+            },
+        };
+        command = Some(cmd);
+        let base = if cmd.is_ascii_lowercase() {
+            current
+        } else {
+            Vec2::ZERO
+        };
+        let upper = cmd.to_ascii_uppercase();
+        let arity = match upper {
+            'M' | 'L' => 2,
+            'H' | 'V' => 1,
+            'C' => 6,
+            'Q' => 4,
+            'Z' => 0,
+            _ => return None,
+        };
+        let mut args = [0.; 6];
+        for arg in args.iter_mut().take(arity) {
+            match tokens.get(i) {
+                Some(SvgToken::Number(x)) => *arg = *x,
+                _ => return None,
+            }
+            i += 1;
+        }
+        let point = |k: usize| base + Vec2::new(args[k], args[k + 1]);
+        if upper == 'M' {
+            current = point(0);
+            start = current;
+            res.push(vec![current]);
+            continue;
+        }
+        let polyline = res.last_mut()?;
+        match upper {
+            'L' => current = point(0),
+            'H' => current.x = base.x + args[0],
+            'V' => current.y = base.y + args[0],
+            'C' => {
+                let (p0, p1, p2, p3) = (current, point(0), point(2), point(4));
+                polyline.extend((1..CURVE_SEGMENTS).map(|s| {
+                    let t = s as f32 / CURVE_SEGMENTS as f32;
+                    let u = 1. - t;
+                    u * u * u * p0 + 3. * u * u * t * p1 + 3. * u * t * t * p2 + t * t * t * p3
+                }));
+                current = p3;
+            }
+            'Q' => {
+                let (p0, p1, p2) = (current, point(0), point(2));
+                polyline.extend((1..CURVE_SEGMENTS).map(|s| {
+                    let t = s as f32 / CURVE_SEGMENTS as f32;
+                    let u = 1. - t;
+                    u * u * p0 + 2. * u * t * p1 + t * t * p2
+                }));
+                current = p2;
+            }
+            _ => current = start,
+        }
+        polyline.push(current);
+    }
+    Some(res)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SvgToken {
+    Command(char),
+    Number(f32),
+}
+
+/// Split SVG path data into commands and numbers, numbers may be packed like `1.5.5-2`
+fn tokenize_svg_path(path: &str) -> Option<Vec<SvgToken>> {
+    let mut res = Vec::new();
+    let chars = path.chars().collect::<Vec<_>>();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == ',' {
+            i += 1;
+        } else if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            res.push(SvgToken::Command(c));
+            i += 1;
+        } else {
+            let st = i;
+            let mut seen_dot = false;
+            let mut seen_exp = false;
+            if chars[i] == '-' || chars[i] == '+' {
+                i += 1;
+            }
+            while i < chars.len() {
+                match chars[i] {
+                    '0'..='9' => {}
+                    '.' if !seen_dot && !seen_exp => seen_dot = true,
+                    'e' | 'E' if !seen_exp => {
+                        seen_exp = true;
+                        if matches!(chars.get(i + 1), Some('-' | '+')) {
+                            i += 1;
+                        }
+                    }
+                    _ => break,
+                }
+                i += 1;
+            }
+            let number = chars[st..i].iter().collect::<String>().parse().ok()?;
+            res.push(SvgToken::Number(number));
+        }
+    }
+    Some(res)
+}
+
+/// Generate `n` `Vec2` evenly spaced along an SVG path, see `parse_svg_path` and `gen_points_polylines`
+///
+/// Return `None` if the path is malformed
+pub fn gen_points_svg_path(path: &str, n: usize, radius: f32) -> Option<Vec<Vec2>> {
+    Some(gen_points_polylines(&parse_svg_path(path)?, n, radius))
+}
+
+/// Generate `n` `Vec2` evenly spaced along polylines
+///
+/// Points are centered at zero and scaled so the farthest one is `radius` away, pass them to
+/// `gen_shape_velocities` to turn them into initial velocities of a burst
+///
+/// # Notes
+///
+/// - Close a polyline by repeating its first point at the end
+/// - Points are spread over all polylines by their length, with no points on the jumps between them
+pub fn gen_points_polylines(polylines: &[Vec<Vec2>], n: usize, radius: f32) -> Vec<Vec2> {
+    let segments = polylines
+        .iter()
+        .flat_map(|p| p.windows(2).map(|w| (w[0], w[1])))
+        .filter(|(a, b)| a != b)
+        .collect::<Vec<_>>();
+    let total = segments.iter().map(|(a, b)| a.distance(*b)).sum::<f32>();
+    if n == 0 || total <= 0. {
+        return Vec::new();
+    }
+    let step = total / n as f32;
+    let mut res = Vec::with_capacity(n);
+    let mut segment = 0;
+    let mut travelled = 0.;
+    for k in 0..n {
+        let target = (k as f32 + 0.5) * step;
+        let (mut a, mut b) = segments[segment];
+        while travelled + a.distance(b) < target && segment + 1 < segments.len() {
+            travelled += a.distance(b);
+            segment += 1;
+            (a, b) = segments[segment];
+        }
+        let t = ((target - travelled) / a.distance(b)).clamp(0., 1.);
+        res.push(a.lerp(b, t));
+    }
+    let (min, max) = res.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(lo, hi), p| (lo.min(*p), hi.max(*p)),
+    );
+    let center = (min + max) / 2.;
+    let extent = res.iter().map(|p| p.distance(center)).fold(0., f32::max);
+    let scale = if extent > 0. { radius / extent } else { 0. };
+    res.iter().map(|p| (*p - center) * scale).collect()
+}
+
+/// Turn target points of a shape into initial velocities of a burst
+///
+/// Under air resistance `ar_scale` and without other forces, a `Particle` starting at the center with the returned velocity
+/// reaches its target point after `arrive_after` seconds, so the burst expands into the shape
+///
+/// # Notes
+///
+/// - Velocity grows exponentially with the distance, keep `ar_scale` small (e.g. `0.05`) for large shapes
+/// - Use a small `gravity_scale` and `shape_hold_force` to keep the shape readable
+pub fn gen_shape_velocities(targets: &[Vec2], ar_scale: f32, arrive_after: f32) -> Vec<Vec2> {
+    targets
+        .iter()
+        .map(|p| {
+            let d = p.length();
+            if d == 0. {
+                return Vec2::ZERO;
+            }
+            // With quadratic air resistance, distance travelled is `ln(1 + k * v0 * t) / k`
+            let speed = if ar_scale > f32::EPSILON {
+                (ar_scale * d).exp_m1() / (ar_scale * arrive_after)
+            } else {
+                d / arrive_after
+            };
+            *p / d * speed
+        })
+        .collect()
+}
+
+/// Additional force that holds a shape made by `gen_shape_velocities` in the sky
+///
+/// From `arrive_after` seconds to `arrive_after + hold` seconds of a `Particle`'s life, it brakes the `Particle`
+/// and cancels gravity of `gravity_scale`, after that gravity takes over
+pub fn shape_hold_force(
+    arrive_after: f32,
+    hold: f32,
+    gravity_scale: f32,
+) -> impl Fn(&mut ForceContext) -> Vec2 + Send + Sync {
+    move |ctx| {
+        let t = ctx.particle.time_elapsed.as_secs_f32();
+        if t >= arrive_after && t < arrive_after + hold {
+            -ctx.particle.vel * 20. - Vec2::Y * 10. * gravity_scale
+        } else {
+            Vec2::ZERO
+        }
+    }
+}
+
 /// Return squared distance between to points
 pub fn distance_squared(a: Vec2, b: Vec2) -> f32 {
     (b.x - a.x).powi(2) + (b.y - a.y).powi(2)
@@ -0,0 +1,112 @@
+mod common;
+
+use std::time::Duration;
+
+use firework_rs::{
+    boundary::{Boundary, GroundBehavior},
+    fireworks::{Firework, FireworkConfig, FireworkManager},
+    particle::ParticleConfig,
+};
+use glam::Vec2;
+
+use common::{launch, Show};
+
+/// A show of one particle at the origin moving at `vel`, without gravity nor air resistance
+fn lone_particle(vel: Vec2, life_time: Duration, boundary: Boundary) -> Show {
+    Show::new(
+        FireworkManager::default()
+            .with_boundary(boundary)
+            .with_firework(Firework {
+                config: FireworkConfig::default()
+                    .with_gravity_scale(0.)
+                    .with_ar_scale(0.),
+                ..launch(vec![ParticleConfig::new(
+                    Vec2::ZERO,
+                    vel,
+                    2,
+                    life_time,
+                    (255, 255, 255),
+                )])
+            }),
+    )
+}
+
+#[test]
+fn particles_bounce_on_the_ground_with_restitution_and_friction() {
+    let boundary = Boundary::default().with_ground(
+        10.,
+        GroundBehavior::Bounce {
+            restitution: 0.5,
+            friction: 0.25,
+        },
+    );
+    let mut show = lone_particle(Vec2::new(4., 20.), Duration::from_secs(10), boundary);
+    show.run(20);
+    let p = show.particles()[0];
+    assert!(p.pos.y <= 10.);
+    assert!((p.vel.x - 3.).abs() < 1e-4);
+    assert!((p.vel.y + 10.).abs() < 1e-4);
+}
+
+#[test]
+fn particles_bounce_back_from_walls() {
+    let boundary = Boundary::default().with_walls(-5., 5., 0.6);
+    let mut show = lone_particle(Vec2::new(20., 0.), Duration::from_secs(10), boundary);
+    show.run(10);
+    let p = show.particles()[0];
+    assert!((-5. ..=5.).contains(&p.pos.x));
+    assert!((p.vel.x + 12.).abs() < 1e-4);
+    show.run(30);
+    // Back from the left wall, slower again
+    let p = show.particles()[0];
+    assert!((-5. ..=5.).contains(&p.pos.x));
+    assert!((p.vel.x - 7.2).abs() < 1e-4);
+}
+
+#[test]
+fn fast_particles_splash_into_sparks() {
+    let splash = |speed: f32, frames: u32| {
+        let boundary = Boundary::default().with_ground(
+            10.,
+            GroundBehavior::Splash {
+                sparks: 5,
+                speed: 0.3,
+                min_speed: 8.,
+            },
+        );
+        let mut show = lone_particle(Vec2::new(0., speed), Duration::from_secs(10), boundary);
+        show.run(frames);
+        show.particles()
+    };
+    // Hits the ground at 0.5 s, sparks last at least 0.3 s
+    let sparks = splash(20., 12);
+    assert_eq!(sparks.len(), 5);
+    assert!(sparks.iter().all(|p| p.pos.y <= 10. && p.vel.y < 0.));
+    assert!(sparks.iter().all(|p| p.vel.length() <= 20. * 0.3 + 1e-4));
+    // Slow particles die without splashing
+    assert!(splash(6., 36).is_empty());
+}
+
+#[test]
+fn settled_embers_live_at_most_ember_time() {
+    let boundary = Boundary::default().with_ground(
+        10.,
+        GroundBehavior::Settle {
+            ember_time: Duration::from_secs_f32(1.5),
+        },
+    );
+    // Lands at 0.5 s and would live 10 s, the ember lasts 1.5 s more
+    let mut show = lone_particle(Vec2::new(0., 20.), Duration::from_secs(10), boundary);
+    show.run(30);
+    let p = show.particles()[0];
+    assert_eq!((p.pos.y, p.vel), (10., Vec2::ZERO));
+    show.run(12);
+    assert!(show.particles().is_empty());
+
+    // Landing near the end of its life doesn't make the particle live longer
+    let mut show = lone_particle(Vec2::new(0., 20.), Duration::from_secs_f32(1.), boundary);
+    show.run(18);
+    assert_eq!(show.particles().len(), 1);
+    show.run(4);
+    assert!(show.particles().is_empty());
+}
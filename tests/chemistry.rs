@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use firework_rs::{
+    chemistry::{blackbody_color, cooling_temperature, Emission, MetalSalt},
+    particle::ParticleConfig,
+};
+use glam::Vec2;
+
+#[test]
+fn blackbody_goes_from_red_to_white_to_blue() {
+    let (r, g, b) = blackbody_color(1500.);
+    assert!(r == 255 && g < 150 && b == 0);
+    let (r, g, b) = blackbody_color(6600.);
+    assert!(r > 240 && g > 240 && b > 240);
+    let (r, _, b) = blackbody_color(15000.);
+    assert!(b == 255 && r < 220);
+}
+
+#[test]
+fn bodies_cool_down_over_their_life() {
+    let temps = (0..=10)
+        .map(|i| cooling_temperature(3000., i as f32 / 10.))
+        .collect::<Vec<_>>();
+    assert_eq!(temps[0], 3000.);
+    assert!(temps.windows(2).all(|w| w[1] < w[0]));
+    assert!(temps[10] > 1000.);
+    // Cooling shifts the color towards red
+    let emission = Emission::Blackbody { temperature: 3000. };
+    assert!(emission.color((0, 0, 0), 1.).1 < emission.color((0, 0, 0), 0.).1);
+}
+
+#[test]
+fn salts_burn_white_hot_then_in_their_color() {
+    for salt in MetalSalt::ALL {
+        let emission = Emission::Salt(salt);
+        let (r, g, b) = emission.color((0, 0, 0), 0.);
+        assert!(r > 200 && g > 200 && b > 150);
+        if salt != MetalSalt::Titanium {
+            assert_eq!(emission.color((0, 0, 0), 0.5), salt.color());
+        }
+    }
+}
+
+#[test]
+fn fixed_emission_keeps_the_color() {
+    let config = ParticleConfig::new(Vec2::ZERO, Vec2::ZERO, 2, Duration::from_secs(1), (1, 2, 3));
+    assert_eq!(config.color_at(0.7), (1, 2, 3));
+    let config = config.with_emission(Emission::Salt(MetalSalt::Barium));
+    // The base color is kept, the emission decides the color over the life
+    assert_eq!(config.color, (1, 2, 3));
+    assert_eq!(
+        config.color_at(0.),
+        Emission::Salt(MetalSalt::Barium).color((0, 0, 0), 0.)
+    );
+    assert_eq!(config.color_at(0.5), MetalSalt::Barium.color());
+}
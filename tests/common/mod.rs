@@ -0,0 +1,62 @@
+//! Helpers shared by the integration tests
+
+// Every test file uses only some of them
+#![allow(dead_code)]
+
+use std::time::{Duration, SystemTime};
+
+use firework_rs::{
+    fireworks::{Firework, FireworkManager},
+    particle::{Particle, ParticleConfig},
+};
+
+/// One frame at 20 fps
+pub const FRAME: Duration = Duration::from_millis(50);
+
+/// Return a `Firework` of `particles` launched at the UNIX epoch, the other fields are default
+pub fn launch(particles: Vec<ParticleConfig>) -> Firework {
+    Firework {
+        init_time: SystemTime::UNIX_EPOCH,
+        particles,
+        ..Default::default()
+    }
+}
+
+/// A `FireworkManager` updated frame by frame from the UNIX epoch
+pub struct Show {
+    pub fm: FireworkManager,
+    /// Number of frames already run
+    pub frames: u32,
+}
+
+impl Show {
+    /// Create a `Show` of `fm` which has not run yet
+    pub fn new(fm: FireworkManager) -> Self {
+        Self { fm, frames: 0 }
+    }
+
+    /// Create a `Show` of `firework` alone
+    pub fn of(firework: Firework) -> Self {
+        Self::new(FireworkManager::default().with_firework(firework))
+    }
+
+    /// Run `frames` more frames of `FRAME`
+    pub fn run(&mut self, frames: u32) -> &mut Self {
+        for _ in 0..frames {
+            self.fm
+                .update(SystemTime::UNIX_EPOCH + FRAME * self.frames, FRAME);
+            self.frames += 1;
+        }
+        self
+    }
+
+    /// Return the alive `Particle`s of the first `Firework`
+    pub fn particles(&self) -> Vec<Particle> {
+        self.fm.fireworks[0].current_particles.iter().collect()
+    }
+
+    /// Return the alive smoke puffs of the first `Firework`
+    pub fn smoke(&self) -> Vec<Particle> {
+        self.fm.fireworks[0].smoke.iter().collect()
+    }
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use firework_rs::{
+    fireworks::{Firework, FireworkConfig},
+    particle::{Particle, ParticleConfig, ParticleRng},
+};
+use glam::Vec2;
+use rand::RngCore;
+
+use common::{launch, Show};
+
+#[test]
+fn force_context_describes_the_particle_and_its_firework() {
+    let center = Vec2::new(30., 12.);
+    // Index, center, firework age and user data seen by `additional_force`
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let record = seen.clone();
+    let config = FireworkConfig::default().with_additional_force(move |ctx| {
+        ctx.user_data[0] += 1.;
+        record.lock().unwrap().push((
+            ctx.index,
+            ctx.center,
+            ctx.firework_age,
+            ctx.dt,
+            ctx.user_data[0],
+        ));
+        Vec2::ZERO
+    });
+    let particles = (0..3)
+        .map(|i| {
+            ParticleConfig::new(
+                center,
+                Vec2::new(i as f32, -5.),
+                2,
+                Duration::from_secs(10),
+                (255, 255, 255),
+            )
+        })
+        .collect();
+    let mut show = Show::of(Firework {
+        center,
+        config,
+        ..launch(particles)
+    });
+    show.run(10);
+
+    let seen = seen.lock().unwrap();
+    let store = &show.fm.fireworks[0].current_particles;
+    let indices = store.indices().collect::<Vec<_>>();
+    assert_eq!(indices.len(), 3);
+    for i in indices {
+        let steps = seen.iter().filter(|s| s.0 == i).collect::<Vec<_>>();
+        assert!(steps.len() > 1);
+        // User data is kept from one step to the next
+        assert_eq!(store.user_data(i)[0], steps.len() as f32);
+        for (k, (_, c, age, dt, count)) in steps.iter().enumerate() {
+            assert_eq!(*c, center);
+            assert_eq!(*count, k as f32 + 1.);
+            assert_eq!(*age, *dt * k as u32);
+        }
+    }
+}
+
+#[test]
+fn particle_rngs_differ_for_every_particle_and_step() {
+    let mut firsts = HashSet::new();
+    let mut count = 0;
+    for index in 0..8 {
+        for t in 0..8 {
+            for (vx, vy) in [(1., 2.), (2., 1.), (-1., 2.), (0., 0.)] {
+                let particle = Particle::new(
+                    Vec2::ZERO,
+                    Vec2::new(vx, vy),
+                    2,
+                    Duration::from_secs(1),
+                    (255, 255, 255),
+                );
+                let mut rng = ParticleRng::new(&particle, index, Duration::from_millis(t));
+                firsts.insert(rng.next_u64());
+                count += 1;
+            }
+        }
+    }
+    assert_eq!(firsts.len(), count);
+
+    // Same inputs give the same stream
+    let particle = Particle::default();
+    let mut a = ParticleRng::new(&particle, 3, Duration::from_millis(7));
+    let mut b = ParticleRng::new(&particle, 3, Duration::from_millis(7));
+    assert_eq!(
+        (0..4).map(|_| a.next_u64()).collect::<Vec<_>>(),
+        (0..4).map(|_| b.next_u64()).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use std::time::Duration;
+
+use firework_rs::{
+    camera::Camera,
+    particle::ParticleConfig,
+    shell::{Shell, ShellKind},
+};
+use glam::{Vec2, Vec3};
+
+use common::{launch, Show};
+
+#[test]
+fn depth_velocity_slows_down_the_flat_motion() {
+    let flat = ParticleConfig::new(
+        Vec2::ZERO,
+        Vec2::new(20., 0.),
+        2,
+        Duration::from_secs(5),
+        (255, 255, 255),
+    );
+    let deep = flat.with_depth(0., 20.);
+    // One second of flight
+    let particles = Show::of(launch(vec![flat, deep])).run(20).particles();
+    assert_eq!(particles[0].z, 0.);
+    assert!(particles[1].z > 0.);
+    // Air resistance depends on the speed in all three dimensions
+    assert!(particles[1].pos.x < particles[0].pos.x);
+    assert!(particles[1].pos.y < particles[0].pos.y);
+}
+
+#[test]
+fn camera_projects_farther_points_closer_to_the_pivot() {
+    let camera = Camera::new(100.).with_orbit_speed(0.);
+    let pivot = Vec2::new(50., 20.);
+    let (near, near_depth) = camera
+        .project(Vec3::new(60., 20., -20.), pivot, Duration::ZERO)
+        .unwrap();
+    let (far, far_depth) = camera
+        .project(Vec3::new(60., 20., 50.), pivot, Duration::ZERO)
+        .unwrap();
+    assert_eq!(
+        camera.project(pivot.extend(0.), pivot, Duration::ZERO),
+        Some((pivot, 0.))
+    );
+    assert!(near.x > 60. && far.x < 60.);
+    assert!(near_depth < far_depth);
+    assert!(camera.brightness(near_depth) > camera.brightness(far_depth));
+    assert_eq!(
+        camera.project(Vec3::new(0., 0., -200.), pivot, Duration::ZERO),
+        None
+    );
+}
+
+#[test]
+fn shells_are_flat_unless_depth_is_set() {
+    let shell = Shell::new(ShellKind::Peony);
+    let flat = shell.build(Vec2::ZERO, Duration::ZERO, false);
+    assert!(flat.particles.iter().all(|p| p.init_vel_z == 0.));
+    let deep = shell
+        .with_depth(true)
+        .build(Vec2::ZERO, Duration::ZERO, false);
+    assert!(deep.particles.iter().any(|p| p.init_vel_z != 0.));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::time::Duration;
+
+use firework_rs::{
+    fireworks::{Firework, FireworkConfig},
+    particle::{Effect, ParticleConfig},
+};
+use glam::Vec2;
+use rand::{rngs::StdRng, SeedableRng};
+
+use common::{launch, Show};
+
+#[test]
+fn strobes_blink_with_their_own_phase() {
+    let strobe = Effect::Strobe { frequency: 2. };
+    let at = |ms: u64| Duration::from_millis(ms);
+    // Shown for the first half of every period of 0.5 s
+    assert!(strobe.is_visible(0, at(0)));
+    assert!(strobe.is_visible(0, at(200)));
+    assert!(!strobe.is_visible(0, at(300)));
+    assert!(strobe.is_visible(0, at(550)));
+    let shown = (0..1000).filter(|t| strobe.is_visible(3, at(*t))).count();
+    assert!((480..=520).contains(&shown));
+    // Particles of a burst don't all blink together
+    assert!((0..8).any(|i| strobe.is_visible(i, at(300))));
+    for effect in [
+        Effect::None,
+        Effect::Glitter { density: 1. },
+        Effect::Crackle {
+            sparks: 4,
+            speed: 5.,
+        },
+    ] {
+        assert!((0..100).all(|t| effect.is_visible(t, at(t as u64 * 37))));
+    }
+}
+
+#[test]
+fn glittering_trails_sparkle_with_their_density() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut count = |effect: Effect| (0..1000).filter(|_| effect.sparkles(&mut rng)).count();
+    assert_eq!(count(Effect::None), 0);
+    assert_eq!(count(Effect::Glitter { density: 0. }), 0);
+    let half = count(Effect::Glitter { density: 1. });
+    assert!((430..=570).contains(&half));
+    let sparse = count(Effect::Glitter { density: 0.2 });
+    assert!((50..=150).contains(&sparse));
+}
+
+#[test]
+fn crackles_pop_into_sparks_near_the_end_of_their_life() {
+    let crackle = ParticleConfig::new(
+        Vec2::ZERO,
+        Vec2::new(2., 0.),
+        2,
+        Duration::from_secs(1),
+        (200, 100, 50),
+    )
+    .with_effect(Effect::Crackle {
+        sparks: 6,
+        speed: 5.,
+    });
+    let mut show = Show::of(Firework {
+        config: FireworkConfig::default().with_gravity_scale(0.),
+        ..launch(vec![crackle])
+    });
+    // Still whole while `Dying`, from 65 % of its life
+    let before = show.run(17).particles();
+    assert_eq!(before.len(), 1);
+    assert_eq!(before[0].config.effect, crackle.effect);
+    let sparks = show.run(2).particles();
+    assert_eq!(sparks.len(), 6);
+    for spark in sparks {
+        assert_eq!(spark.config.effect, Effect::None);
+        assert!(spark.config.life_time <= Duration::from_millis(300));
+        assert!(spark.pos.distance(before[0].pos) < 2.);
+    }
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use firework_rs::field::{total_force, ForceField};
+use glam::Vec2;
+
+#[test]
+fn attractors_pull_with_falloff_and_repulsors_push() {
+    let center = Vec2::new(10., 10.);
+    let field = ForceField::attractor(center, 20.);
+    let near = field.force(Vec2::new(14., 10.), Vec2::ZERO, Duration::ZERO);
+    let far = field.force(Vec2::new(18., 10.), Vec2::ZERO, Duration::ZERO);
+    assert!(near.x < 0. && near.y.abs() < 1e-6);
+    assert!((near.length() - 5.).abs() < 1e-5);
+    assert!((far.length() - near.length() / 2.).abs() < 1e-5);
+    // Force stops growing within the radius
+    let inside = field.force(Vec2::new(10.5, 10.), Vec2::ZERO, Duration::ZERO);
+    assert!((inside.length() - 20.).abs() < 1e-5);
+    assert_eq!(field.force(center, Vec2::ZERO, Duration::ZERO), Vec2::ZERO);
+    let repulsor = ForceField::repulsor(center, 20.);
+    assert_eq!(
+        repulsor.force(Vec2::new(14., 10.), Vec2::ZERO, Duration::ZERO),
+        -near
+    );
+}
+
+#[test]
+fn vortices_push_perpendicular_to_the_radius() {
+    let center = Vec2::new(-3., 5.);
+    let field = ForceField::vortex(center, 12.);
+    for pos in [Vec2::new(1., 5.), Vec2::new(-3., -2.), Vec2::new(4., 9.)] {
+        let f = field.force(pos, Vec2::ZERO, Duration::ZERO);
+        assert!(f.dot(pos - center).abs() < 1e-4);
+        assert!((f.length() - 12. / pos.distance(center)).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn gusts_stay_within_bounds() {
+    let force = Vec2::new(3., -1.);
+    assert_eq!(
+        ForceField::wind(force).force(Vec2::ZERO, Vec2::ZERO, Duration::from_secs(7)),
+        force
+    );
+    let field = ForceField::gusty_wind(force, 0.5, Duration::from_secs(2));
+    let strengths = (0..500)
+        .map(|i| {
+            let f = field.force(Vec2::ZERO, Vec2::ZERO, Duration::from_millis(i * 37));
+            // Gusts change the strength of the wind, never its direction
+            assert!(f.perp_dot(force).abs() < 1e-4);
+            f.length() / force.length()
+        })
+        .collect::<Vec<_>>();
+    assert!(strengths
+        .iter()
+        .all(|s| (0.5 - 1e-5..=1.5 + 1e-5).contains(s)));
+    assert!(strengths.iter().any(|s| (s - 1.).abs() > 0.05));
+}
+
+#[test]
+fn turbulence_is_finite_and_divergence_free() {
+    let field = ForceField::turbulence(20.);
+    let time = Duration::from_millis(1300);
+    let force = |p: Vec2| field.force(p, Vec2::ZERO, time);
+    // Same step as the finite differences of the noise, with the default scale of 10
+    let h = 0.1;
+    let mut largest = 0_f32;
+    for i in 0..200 {
+        let p = Vec2::new(i as f32 * 1.37 - 80., (i * 7 % 113) as f32 * 0.91 - 40.);
+        let f = force(p);
+        assert!(f.is_finite());
+        largest = largest.max(f.length());
+        let div = (force(p + Vec2::X * h).x - force(p - Vec2::X * h).x + force(p + Vec2::Y * h).y
+            - force(p - Vec2::Y * h).y)
+            / (2. * h);
+        assert!(div.abs() < 0.01, "divergence {} at {}", div, p);
+    }
+    assert!(largest > 1.);
+    assert!(total_force(&[field], Vec2::new(1e6, -1e6), Vec2::ZERO, time).is_finite());
+}
+
+#[test]
+fn turbulence_is_finite_far_away() {
+    let field = ForceField::turbulence(20.);
+    let gusty = ForceField::gusty_wind(Vec2::X, 0.5, Duration::from_secs(1));
+    for p in [
+        Vec2::new(1e12, -1e12),
+        Vec2::splat(f32::MAX),
+        Vec2::splat(-f32::MAX),
+    ] {
+        assert!(field.force(p, Vec2::ZERO, Duration::MAX).is_finite());
+    }
+    assert!(gusty
+        .force(
+            Vec2::ZERO,
+            Vec2::ZERO,
+            Duration::from_secs(u32::MAX as u64 * 2)
+        )
+        .is_finite());
+}
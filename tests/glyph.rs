@@ -0,0 +1,151 @@
+use firework_rs::{
+    config::Config,
+    glyph::{char_width, GlyphPalette},
+    particle::LifeState,
+    term::{Terminal, WIDE_TAIL},
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn glyphs_are_picked_from_the_band_of_the_density() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let ascii = GlyphPalette::ASCII;
+    for _ in 0..50 {
+        assert!("$@B%8&WM#".contains(ascii.get_char(LifeState::Alive, 0.9, &mut rng).unwrap()));
+        assert!("`'. ".contains(ascii.get_char(LifeState::Alive, 0.1, &mut rng).unwrap()));
+        // Densities above the last band use the last band
+        assert!("xrjft*".contains(ascii.get_char(LifeState::Declining, 2., &mut rng).unwrap()));
+    }
+    assert_eq!(ascii.get_char(LifeState::Dead, 0.5, &mut rng), None);
+}
+
+#[test]
+fn named_palettes_exist() {
+    for name in GlyphPalette::NAMES {
+        assert!(GlyphPalette::named(name).is_some());
+    }
+    assert_eq!(GlyphPalette::named("Braille"), Some(GlyphPalette::BRAILLE));
+    assert!(GlyphPalette::named("nope").is_none());
+}
+
+#[test]
+fn palette_is_parsed_from_text() {
+    let text = "# stars\nalive 1 *+\nalive 0.5 . \ndeclining 1 +\ndying 1 .  ,\n";
+    let glyphs = GlyphPalette::parse(text).unwrap();
+    assert_eq!(glyphs.alive.len(), 2);
+    // Bands are sorted, and spaces are glyphs too
+    assert_eq!(glyphs.alive[0].glyphs, ". ");
+    assert_eq!(glyphs.dying[0].glyphs, ".  ,");
+    assert!(GlyphPalette::parse("alive 1 *\ndeclining 1 +").is_err());
+    assert!(GlyphPalette::parse("alive x *\ndeclining 1 +\ndying 1 .").is_err());
+    assert!(GlyphPalette::parse("burning 1 *").is_err());
+    // Sparkles default to the densest declining band
+    assert_eq!(glyphs.sparkles, "+");
+    let glyphs = GlyphPalette::parse(&format!("{}sparkle * +", text)).unwrap();
+    assert_eq!(glyphs.sparkles, "* +");
+    assert!(GlyphPalette::parse(&format!("{}sparkle", text)).is_err());
+    // The first two fields may be separated by any whitespace
+    let tabs = GlyphPalette::parse("alive\t1\t*+\n  declining   0.5 +\ndying 1 .").unwrap();
+    assert_eq!(tabs.alive[0].glyphs, "*+");
+    assert_eq!(tabs.declining[0].max_density, 0.5);
+    assert_eq!(tabs.declining[0].glyphs, "+");
+}
+
+#[test]
+fn sparkles_are_picked_from_the_palette() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..50 {
+        assert!("*+".contains(GlyphPalette::ASCII.get_sparkle(&mut rng).unwrap()));
+        // Sparkles of cjk palettes fill the whole cell
+        for glyphs in [GlyphPalette::CJK, GlyphPalette::KANA] {
+            assert_eq!(char_width(glyphs.get_sparkle(&mut rng).unwrap()), 2);
+        }
+    }
+}
+
+#[test]
+fn config_picks_glyphs() {
+    let mut cfg = Config::default();
+    assert_eq!(cfg.glyphs(), &GlyphPalette::ASCII);
+    cfg.enable_cjk = true;
+    assert_eq!(cfg.glyphs(), &GlyphPalette::CJK);
+    cfg.glyphs = Some(GlyphPalette::EMOJI);
+    assert_eq!(cfg.glyphs(), &GlyphPalette::EMOJI);
+}
+
+#[test]
+fn wide_characters_take_two_columns() {
+    assert_eq!(char_width('a'), 1);
+    assert_eq!(char_width('⣿'), 1);
+    assert_eq!(char_width('─'), 1);
+    assert_eq!(char_width('龜'), 2);
+    assert_eq!(char_width('フ'), 2);
+    assert_eq!(char_width('🎆'), 2);
+    assert_eq!(char_width('✨'), 2);
+}
+
+/// Return the text of every line printed by `term`, without the escape sequences
+fn printed_lines(term: &Terminal, cfg: &Config) -> Vec<String> {
+    let mut out = Vec::new();
+    term.print(&mut out, cfg);
+    let out = String::from_utf8(out).unwrap();
+    let mut lines = Vec::new();
+    let mut chars = out.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // A control sequence ends with a letter, moving the cursor starts a line
+            let end = chars.by_ref().find(|c| c.is_ascii_alphabetic()).unwrap();
+            if end == 'H' {
+                lines.push(String::new());
+            }
+        } else {
+            lines.last_mut().unwrap().push(c);
+        }
+    }
+    lines
+}
+
+fn columns(line: &str) -> usize {
+    line.chars().map(char_width).sum()
+}
+
+#[test]
+fn wide_glyphs_cover_the_next_cell() {
+    let cfg = Config::default().with_glyphs(GlyphPalette::EMOJI);
+    let mut term = Terminal::from_size((9, 3));
+    let bitmap = vec![vec![true; 9]];
+    term.draw_bitmap(&bitmap, (4, 1), (1, 1), (255, 200, 0), 1., true, &cfg);
+    let row = &term.screen[1];
+    for x in (0..8).step_by(2) {
+        assert_eq!(char_width(row[x].text), 2);
+        assert_eq!(row[x + 1].text, WIDE_TAIL);
+    }
+    // A wide glyph doesn't fit in the last column
+    assert_eq!(row[8].text, ' ');
+
+    let lines = printed_lines(&term, &cfg);
+    assert_eq!(lines.len(), 3);
+    assert!(lines.iter().all(|l| columns(l) == 9));
+    assert_eq!(lines[1].chars().filter(|c| *c != ' ').count(), 4);
+}
+
+#[test]
+fn cjk_cells_are_two_columns_wide() {
+    let cfg = Config::new(true);
+    let mut term = Terminal::from_size((6, 2));
+    term.draw_bitmap(
+        &[vec![true; 3]],
+        (3, 0),
+        (1, 1),
+        (255, 200, 0),
+        1.,
+        true,
+        &cfg,
+    );
+    term.screen[1][0].text = '*';
+    let lines = printed_lines(&term, &cfg);
+    assert!(lines.iter().all(|l| columns(l) == 12));
+    // Wide glyphs fill their cell, narrow ones are followed by a space
+    assert_eq!(columns(lines[0].trim()), 6);
+    assert!(lines[1].starts_with("* "));
+}
@@ -0,0 +1,68 @@
+use firework_rs::{image::Image, utils::gen_points_image};
+use glam::Vec2;
+
+#[test]
+fn ascii_and_binary_ppm_are_decoded() {
+    let ascii = Image::from_ppm(b"P3\n# a comment\n2 1\n15\n15 0 0  0 15 15\n").unwrap();
+    assert_eq!(ascii.width, 2);
+    assert_eq!(ascii.height, 1);
+    assert_eq!(ascii.pixels, vec![(255, 0, 0, 255), (0, 255, 255, 255)]);
+    let mut binary = b"P6 2 1 255\n".to_vec();
+    binary.extend([255, 0, 0, 0, 255, 255]);
+    assert_eq!(Image::from_ppm(&binary).unwrap(), ascii);
+    assert!(Image::from_ppm(b"P6 4 4 255\n\x00\x00").is_err());
+    assert!(Image::from_ppm(b"P5 1 1 255\n\x00").is_err());
+}
+
+#[cfg(feature = "png")]
+#[test]
+fn png_is_decoded() {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&[255, 0, 0, 255, 0, 0, 255, 0])
+            .unwrap();
+    }
+    let image = Image::from_png(&bytes).unwrap();
+    assert_eq!(image.pixels, vec![(255, 0, 0, 255), (0, 0, 255, 0)]);
+}
+
+#[test]
+fn image_is_down_sampled_to_the_particle_count() {
+    let image = Image::new(40, 20, vec![(200, 100, 50, 255); 800]);
+    let full = gen_points_image(&image, 800, 1.);
+    assert_eq!(full.len(), 800);
+    assert!(full.iter().all(|(_, c)| *c == (200, 100, 50)));
+    let sampled = gen_points_image(&image, 100, 1.);
+    assert!(sampled.len() <= 100 && sampled.len() >= 50);
+    // Points stay centered
+    let sum = sampled.iter().map(|(p, _)| *p).sum::<Vec2>();
+    assert!(sum.length() < 1e-3);
+    assert!(gen_points_image(&image, 0, 1.).is_empty());
+
+    // Block size follows the lit pixels, not the size of the image
+    let mut pixels = vec![(0, 0, 0, 255); 200 * 200];
+    for y in 90..110 {
+        for x in 60..80 {
+            pixels[x + y * 200] = (255, 255, 255, 255);
+        }
+    }
+    let sparse = gen_points_image(&Image::new(200, 200, pixels), 100, 1.);
+    assert_eq!(sparse.len(), 100);
+}
+
+#[test]
+fn background_pixels_get_no_point() {
+    let image = Image::new(
+        3,
+        1,
+        vec![(0, 0, 0, 255), (255, 255, 255, 0), (10, 200, 10, 255)],
+    );
+    assert_eq!(
+        gen_points_image(&image, 10, 2.),
+        vec![(Vec2::new(2., 0.), (10, 200, 10))]
+    );
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use firework_rs::{
+    fireworks::{Firework, FireworkConfig, FireworkManager},
+    integrator::Integrator,
+    particle::ParticleConfig,
+};
+use glam::Vec2;
+
+use common::launch;
+
+const INTEGRATORS: [Integrator; 3] = [
+    Integrator::SemiImplicitEuler,
+    Integrator::Verlet,
+    Integrator::Rk4,
+];
+
+/// Four particles from one center, the last one at rest
+fn four_particles(integrator: Integrator, gravity_scale: f32, ar_scale: f32) -> FireworkManager {
+    let center = Vec2::new(40., 20.);
+    let particles = [
+        Vec2::new(30., -40.),
+        Vec2::new(-55., -10.),
+        Vec2::new(0., 80.),
+        Vec2::ZERO,
+    ]
+    .iter()
+    .map(|v| ParticleConfig::new(center, *v, 5, Duration::from_secs(10), (255, 255, 255)))
+    .collect();
+    let config = FireworkConfig::default()
+        .with_gravity_scale(gravity_scale)
+        .with_ar_scale(ar_scale)
+        .with_integrator(integrator);
+    FireworkManager::default().with_firework(Firework {
+        center,
+        config,
+        ..launch(particles)
+    })
+}
+
+/// Run the show at `fps` for `secs` seconds and return positions of all the particles
+fn run_at_fps(mut fm: FireworkManager, fps: u32, secs: u32) -> Vec<Vec2> {
+    // Frame `k` starts at `k / fps` seconds, so frames add up exactly to `secs` seconds
+    let frame_start = |k: u32| Duration::from_secs(k as u64) / fps;
+    for k in 0..fps * secs {
+        fm.update(
+            SystemTime::UNIX_EPOCH + frame_start(k),
+            frame_start(k + 1) - frame_start(k),
+        );
+    }
+    fm.fireworks[0]
+        .current_particles
+        .iter()
+        .map(|p| p.pos)
+        .collect()
+}
+
+#[test]
+fn trajectory_does_not_depend_on_frame_rate() {
+    for integrator in INTEGRATORS {
+        let at_5 = run_at_fps(four_particles(integrator, 1., 0.28), 5, 2);
+        let at_20 = run_at_fps(four_particles(integrator, 1., 0.28), 20, 2);
+        let at_60 = run_at_fps(four_particles(integrator, 1., 0.28), 60, 2);
+        assert_eq!(at_5.len(), 4);
+        assert_eq!(at_5, at_20, "{:?} at 5 fps and 20 fps", integrator);
+        assert_eq!(at_5, at_60, "{:?} at 5 fps and 60 fps", integrator);
+    }
+}
+
+#[test]
+fn particle_at_rest_has_no_nan() {
+    for integrator in INTEGRATORS {
+        let pos = run_at_fps(four_particles(integrator, 0., 0.28), 20, 1);
+        assert_eq!(pos[3], Vec2::new(40., 20.), "{:?}", integrator);
+    }
+}
+
+#[test]
+fn trajectory_matches_projectile_motion_without_air_resistance() {
+    for integrator in INTEGRATORS {
+        let pos = run_at_fps(four_particles(integrator, 1., 0.), 20, 1);
+        // x = x0 + v * t, y = y0 + v * t + g * t^2 / 2, with g = 10 and t = 1
+        let expected = Vec2::new(40. + 30., 20. - 40. + 5.);
+        assert!(
+            pos[0].distance(expected) < 0.05,
+            "{:?} ended at {:?}, expected {:?}",
+            integrator,
+            pos[0],
+            expected
+        );
+    }
+}
+
+#[test]
+fn unsimulated_time_is_carried_over() {
+    let mut fm = four_particles(Integrator::default(), 1., 0.28);
+    fm.update(SystemTime::UNIX_EPOCH, Duration::from_micros(2500));
+    assert_eq!(fm.accumulator, Duration::from_micros(500));
+}
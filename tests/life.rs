@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use firework_rs::{
+    fireworks::FireworkConfig,
+    particle::{LifeCurve, LifeState, Particle},
+};
+use glam::Vec2;
+
+fn states_over_life(mut particle: Particle, config: &FireworkConfig) -> Vec<LifeState> {
+    (0..10)
+        .map(|_| {
+            particle.step(Duration::from_millis(100), config);
+            particle.life_state
+        })
+        .collect()
+}
+
+#[test]
+fn default_curve_keeps_the_old_thresholds() {
+    let curve = LifeCurve::default();
+    assert_eq!(curve.life_state(0.39), LifeState::Alive);
+    assert_eq!(curve.life_state(0.4), LifeState::Declining);
+    assert_eq!(curve.life_state(0.64), LifeState::Declining);
+    assert_eq!(curve.life_state(0.65), LifeState::Dying);
+    assert_eq!(curve.life_state(1.), LifeState::Dead);
+    assert_eq!(curve.intensity(0.9), 1.);
+}
+
+#[test]
+fn intensity_is_clamped() {
+    let curve = LifeCurve::new(0.5, 0.8).with_intensity(|t| 2. - 4. * t);
+    assert_eq!(curve.intensity(0.), 1.);
+    assert_eq!(curve.intensity(0.5), 0.);
+    assert_eq!(curve.intensity(-1.), 1.);
+}
+
+#[test]
+fn willows_stay_bright_and_crackles_die_abruptly() {
+    let willow = LifeCurve::WILLOW;
+    let abrupt = LifeCurve::ABRUPT;
+    assert_eq!(willow.life_state(0.6), LifeState::Alive);
+    assert!(willow.intensity(0.5) > 0.9);
+    assert!(willow.intensity(0.95) < 0.3);
+    assert_eq!(abrupt.intensity(0.8), 1.);
+    assert!(abrupt.intensity(0.99) < 0.1);
+}
+
+#[test]
+fn particle_curve_overrides_firework_curve() {
+    let particle = Particle::new(
+        Vec2::ZERO,
+        Vec2::ZERO,
+        2,
+        Duration::from_secs(1),
+        (255, 255, 255),
+    );
+    let firework = FireworkConfig::default().with_life_curve(LifeCurve::new(0.8, 0.9));
+    let states = states_over_life(particle, &firework);
+    assert_eq!(states[6], LifeState::Alive);
+    assert_eq!(states[7], LifeState::Declining);
+    assert_eq!(states[8], LifeState::Dying);
+
+    let mut particle = particle;
+    particle.config = particle.config.with_life_curve(LifeCurve::new(0.1, 0.2));
+    assert_eq!(
+        firework.life_curve_of(&particle.config),
+        LifeCurve::new(0.1, 0.2)
+    );
+    let states = states_over_life(particle, &firework);
+    assert_eq!(states[0], LifeState::Declining);
+    assert_eq!(states[1], LifeState::Dying);
+    assert_eq!(states[9], LifeState::Dead);
+}
+
+#[test]
+fn thresholds_are_clamped_in_order() {
+    let curve = LifeCurve::new(0.7, 0.3);
+    assert_eq!((curve.declining, curve.dying), (0.7, 0.7));
+    assert_eq!(LifeCurve::new(-1., 2.).thresholds(), (0., 1.));
+    assert_eq!(LifeCurve::new(f32::NAN, 0.5).thresholds(), (0., 0.5));
+    // Curves built from their fields are clamped when they are used
+    let curve = LifeCurve {
+        declining: 0.9,
+        dying: 0.2,
+        intensity: None,
+    };
+    assert_eq!(curve.life_state(0.5), LifeState::Alive);
+    assert_eq!(curve.life_state(0.95), LifeState::Dying);
+    assert_eq!(curve, LifeCurve::new(0.9, 0.9));
+    // Intensity functions are not compared
+    assert_eq!(
+        LifeCurve::WILLOW,
+        LifeCurve::new(0.7, 0.9).with_intensity(|t| t)
+    );
+}
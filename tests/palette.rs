@@ -0,0 +1,68 @@
+use firework_rs::palette::{
+    hsl_to_rgb, oklch_to_rgb, parse_color, rgb_to_hsl, rgb_to_oklch, ColorSpace, Harmony, Palette,
+    EMBER, NAMED,
+};
+
+#[test]
+fn named_palettes_are_found_ignoring_case() {
+    assert_eq!(Palette::named("Ember").unwrap().colors, EMBER.to_vec());
+    assert!(Palette::named("nope").is_none());
+    assert!(NAMED.iter().all(|(_, c)| !c.is_empty()));
+}
+
+#[test]
+fn colors_round_trip_through_hsl_and_oklch() {
+    for c in [(255, 102, 75), (0, 53, 102), (128, 128, 128), (0, 0, 0)] {
+        assert_eq!(hsl_to_rgb(rgb_to_hsl(c)), c);
+        let back = oklch_to_rgb(rgb_to_oklch(c));
+        assert!((back.0 as i16 - c.0 as i16).abs() <= 1);
+        assert!((back.1 as i16 - c.1 as i16).abs() <= 1);
+        assert!((back.2 as i16 - c.2 as i16).abs() <= 1);
+    }
+}
+
+#[test]
+fn harmonies_rotate_the_hue() {
+    let p = Palette::harmony((255, 0, 0), Harmony::Complementary, ColorSpace::Hsl);
+    assert_eq!(p.colors.len(), 4);
+    assert_eq!(p.colors[0], (255, 0, 0));
+    assert_eq!(p.colors[2], (0, 255, 255));
+    let p = Palette::harmony((255, 0, 0), Harmony::Triadic, ColorSpace::Hsl);
+    assert_eq!(
+        [p.colors[0], p.colors[2], p.colors[4]],
+        [(255, 0, 0), (0, 255, 0), (0, 0, 255)]
+    );
+    // Hues of an OKLCH harmony keep the perceptual lightness of the base color
+    let base = (200, 80, 60);
+    let p = Palette::harmony(base, Harmony::Analogous, ColorSpace::Oklch);
+    let l = rgb_to_oklch(base).0;
+    assert!(p
+        .colors
+        .iter()
+        .step_by(2)
+        .all(|c| (rgb_to_oklch(*c).0 - l).abs() < 0.02));
+}
+
+#[test]
+fn palettes_are_parsed_from_text() {
+    let text = "# comment\n\nsunset: #ff5f6d #ffc371 255,255,255\nsea: #0077b6\n";
+    let palettes = Palette::parse(text).unwrap();
+    assert_eq!(palettes.len(), 2);
+    assert_eq!(palettes[0].name, "sunset");
+    assert_eq!(
+        palettes[0].colors,
+        vec![(255, 95, 109), (255, 195, 113), (255, 255, 255)]
+    );
+    assert!(Palette::parse("sunset #ff5f6d").is_err());
+    assert!(Palette::parse("sunset: #ff5f6").is_err());
+    assert_eq!(parse_color(" 1, 2,3 "), Some((1, 2, 3)));
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn palettes_are_parsed_from_json() {
+    let json = r##"{"sunset": ["#ff5f6d", [255, 195, 113]]}"##;
+    let palettes = Palette::parse(json).unwrap();
+    assert_eq!(palettes[0].colors, vec![(255, 95, 109), (255, 195, 113)]);
+    assert!(Palette::parse(r#"{"sunset": [[256, 0, 0]]}"#).is_err());
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use std::time::Duration;
+
+use firework_rs::{
+    fireworks::{ExplosionForm, Firework, FireworkConfig, FireworkManager},
+    integrator::Integrator,
+    particle::{Particle, ParticleConfig},
+    utils::gen_points_circle_normal,
+};
+use glam::Vec2;
+use rand::Rng;
+
+use common::{launch, Show, FRAME};
+
+/// Frames of every run, short enough to keep the tests quick in debug builds
+const FRAMES: u32 = 4;
+
+/// Additional force depending on the state of the `Particle`, so every step of every `Particle` counts
+fn test_config(integrator: Integrator) -> FireworkConfig {
+    FireworkConfig::default()
+        .with_integrator(integrator)
+        .with_additional_force(|ctx| Vec2::new(ctx.particle.pos.y.sin(), ctx.particle.vel.x * 0.1))
+}
+
+/// A show large enough to be split between threads, stores of more than 512 particles are split in chunks
+fn split_show(integrator: Integrator, velocities: &[Vec<Vec2>]) -> Show {
+    let fireworks = velocities
+        .iter()
+        .enumerate()
+        .map(|(i, vels)| {
+            let center = Vec2::new(20. * i as f32, 10.);
+            Firework {
+                center,
+                config: test_config(integrator),
+                ..launch(
+                    vels.iter()
+                        .map(|v| {
+                            ParticleConfig::new(center, *v, 5, Duration::from_secs(60), (255, 0, 0))
+                        })
+                        .collect(),
+                )
+            }
+        })
+        .collect();
+    Show::new(FireworkManager::new(fireworks))
+}
+
+/// Run `FRAMES` frames of `show` and return position, velocity and trail of every `Particle` of every `Firework`
+fn run(show: &mut Show) -> Vec<(Vec2, Vec2, Vec<Vec2>)> {
+    show.run(FRAMES)
+        .fm
+        .fireworks
+        .iter()
+        .flat_map(|f| {
+            f.current_particles.indices().map(|i| {
+                let p = f.current_particles.get(i).unwrap();
+                (p.pos, p.vel, f.current_particles.trail(i).collect())
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn update_matches_stepping_each_particle_alone() {
+    let velocities = vec![
+        gen_points_circle_normal(60., 1200),
+        gen_points_circle_normal(40., 300),
+    ];
+    for integrator in [
+        Integrator::SemiImplicitEuler,
+        Integrator::Verlet,
+        Integrator::Rk4,
+    ] {
+        let res = run(&mut split_show(integrator, &velocities));
+        let config = test_config(integrator);
+        let expected = velocities
+            .iter()
+            .enumerate()
+            .flat_map(|(i, vels)| {
+                vels.iter().map(move |v| {
+                    Particle::new(
+                        Vec2::new(20. * i as f32, 10.),
+                        *v,
+                        5,
+                        Duration::from_secs(60),
+                        (255, 0, 0),
+                    )
+                })
+            })
+            .map(|mut p| {
+                for _ in 0..(FRAME.as_millis() * FRAMES as u128) {
+                    p.step(Duration::from_millis(1), &config);
+                }
+                (p.pos, p.vel)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            res.iter().map(|(p, v, _)| (*p, *v)).collect::<Vec<_>>(),
+            expected,
+            "{:?}",
+            integrator
+        );
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn update_does_not_depend_on_thread_count() {
+    let velocities = (0..4)
+        .map(|_| gen_points_circle_normal(60., 1200))
+        .collect::<Vec<_>>();
+    let run_on = |threads: usize| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let mut show = split_show(Integrator::default(), &velocities);
+        pool.install(|| run(&mut show))
+    };
+    let serial = run_on(1);
+    assert_eq!(serial.len(), 4 * 1200);
+    assert_eq!(serial, run_on(4));
+    assert_eq!(serial, run_on(16));
+}
+
+/// Digest of the bits of every position, velocity and trail point
+fn digest(particles: &[(Vec2, Vec2, Vec<Vec2>)]) -> u64 {
+    particles
+        .iter()
+        .flat_map(|(p, v, trail)| [*p, *v].into_iter().chain(trail.iter().copied()))
+        .flat_map(|v| [v.x.to_bits(), v.y.to_bits()])
+        .fold(0xcbf2_9ce4_8422_2325, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// The serial and the `rayon` builds of `ParticleStore::advance` can't run in the same test binary,
+/// so both must give this digest, which only changes when the simulation changes on purpose
+///
+/// The show avoids `sin` and `cos`, whose last bit may depend on the platform
+#[test]
+fn update_gives_the_same_result_with_and_without_rayon() {
+    let particles = (0..1200)
+        .map(|i| {
+            let vel = Vec2::new((i % 40) as f32 - 20., (i / 40) as f32 - 15.) * 3.;
+            ParticleConfig::new(Vec2::ZERO, vel, 5, Duration::from_secs(60), (255, 0, 0))
+        })
+        .collect::<Vec<_>>();
+    let config = || {
+        FireworkConfig::default().with_additional_force(|ctx| {
+            Vec2::new(ctx.rng.gen_range(-1. ..1.), ctx.particle.vel.x * 0.1)
+        })
+    };
+    let mut show = Show::new(FireworkManager::new(vec![
+        Firework {
+            config: config(),
+            ..launch(particles.clone())
+        },
+        // Picks its particles with the `ParticleRng` of the `Firework`
+        Firework {
+            config: config(),
+            form: ExplosionForm::Sustained {
+                lasts: Duration::from_secs(1),
+                time_interval: Duration::from_millis(10),
+                timer: Duration::ZERO,
+            },
+            ..launch(particles)
+        },
+    ]));
+    let res = run(&mut show);
+    assert_eq!(res.len(), 1200 + 19);
+    assert_eq!(digest(&res), 17_696_497_504_607_857_532);
+}
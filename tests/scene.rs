@@ -0,0 +1,118 @@
+use std::time::{Duration, SystemTime};
+
+use firework_rs::{
+    fireworks::{Firework, FireworkManager},
+    particle::ParticleConfig,
+    scene::{Scene, Scenery},
+};
+use glam::Vec2;
+
+const T: Duration = Duration::ZERO;
+
+/// Return the cells of `scenery` drawn with one of `chars`
+fn cells_of(scenery: &Scenery, chars: &str) -> Vec<(usize, usize)> {
+    let (w, h) = scenery.size;
+    (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .filter(|&(x, y)| {
+            scenery
+                .cell(x, y, T)
+                .is_some_and(|(c, _)| chars.contains(c))
+        })
+        .collect()
+}
+
+/// Return the width and height in cells of the bounding box of `cells`
+fn extent(cells: &[(usize, usize)]) -> (usize, usize) {
+    let w = cells.iter().map(|c| c.0).max().unwrap() - cells.iter().map(|c| c.0).min().unwrap();
+    let h = cells.iter().map(|c| c.1).max().unwrap() - cells.iter().map(|c| c.1).min().unwrap();
+    (w + 1, h + 1)
+}
+
+#[test]
+fn blank_scene_is_empty() {
+    let scenery = Scenery::new(Scene::Blank, 80, 30, 1, 0);
+    assert!(cells_of(&scenery, "*+.@o#").is_empty());
+}
+
+#[test]
+fn night_has_a_round_moon_and_stars_but_no_building() {
+    let ascii = Scenery::new(Scene::Night, 80, 30, 1, 0);
+    let moon = cells_of(&ascii, "@o");
+    assert!(!moon.is_empty());
+    assert!(moon.iter().all(|&(x, y)| x > 40 && y < 15));
+    // Ascii cells are half as wide as they are high, cjk cells are square
+    let (w, h) = extent(&moon);
+    assert!((w as f32 / h as f32 - 2.).abs() < 0.5);
+    let cjk = Scenery::new(Scene::Night, 40, 30, 2, 0);
+    let (w, h) = extent(&cells_of(&cjk, "@o"));
+    assert!((w as f32 / h as f32 - 1.).abs() < 0.3);
+
+    assert!(!cells_of(&ascii, "*+.").is_empty());
+    assert!(cells_of(&ascii, "#").is_empty());
+}
+
+#[test]
+fn stars_twinkle_over_time() {
+    let scenery = Scenery::new(Scene::Night, 80, 30, 1, 0);
+    let stars = cells_of(&scenery, "*+.");
+    let twinkled = stars.iter().any(|&(x, y)| {
+        let at = |ms| scenery.cell(x, y, Duration::from_millis(ms));
+        (0..20).any(|k| at(k * 150) != at(0))
+    });
+    assert!(twinkled);
+}
+
+#[test]
+fn city_skyline_stands_on_the_bottom_and_is_seeded() {
+    let city = Scenery::new(Scene::City, 80, 30, 1, 7);
+    let buildings = cells_of(&city, "#");
+    assert!(!buildings.is_empty());
+    // Every column of a building goes down to the bottom row
+    for &(x, y) in &buildings {
+        assert!((y..30).all(|y| city.cell(x, y, T).is_some_and(|(c, _)| c == '#')));
+    }
+    let same = Scenery::new(Scene::City, 80, 30, 1, 7);
+    let other = Scenery::new(Scene::City, 80, 30, 1, 8);
+    assert_eq!(cells_of(&same, "#"), buildings);
+    assert_ne!(cells_of(&other, "#"), buildings);
+}
+
+#[test]
+fn skyline_ends_for_any_cell_width() {
+    for cell_width in [2, 19, 20, 64] {
+        let city = Scenery::new(Scene::City, 40, 30, cell_width, 3);
+        assert!(!cells_of(&city, "#").is_empty());
+    }
+}
+
+#[test]
+fn bursts_light_up_the_buildings_nearby() {
+    let mut city = Scenery::new(Scene::City, 80, 30, 1, 7);
+    // Top of the first building, its bottom row is a wall
+    let (x, roof) = cells_of(&city, "#")
+        .into_iter()
+        .min_by_key(|&(x, y)| (x, y))
+        .unwrap();
+    let wall = |city: &Scenery| city.cell(x, 29, T).unwrap().1;
+    let dark = wall(&city);
+    let burst = ParticleConfig::new(
+        Vec2::new(x as f32, roof as f32 - 1.),
+        Vec2::ZERO,
+        2,
+        Duration::from_secs(10),
+        (255, 60, 60),
+    );
+    let mut fm = FireworkManager::default().with_firework(Firework {
+        init_time: SystemTime::UNIX_EPOCH,
+        particles: vec![burst; 20],
+        ..Default::default()
+    });
+    fm.update(SystemTime::UNIX_EPOCH, Duration::from_millis(50));
+    for _ in 0..20 {
+        city.update_light(&fm, |p| Some(p.truncate()));
+    }
+    let lit = wall(&city);
+    assert_ne!(lit, dark);
+    assert!(lit.0 > dark.0 && lit.0 > lit.2);
+}
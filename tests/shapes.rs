@@ -0,0 +1,230 @@
+use std::f32::consts::PI;
+
+use std::time::Duration;
+
+use firework_rs::{
+    shell::{Shell, ShellKind},
+    utils::{
+        gen_points_circle_normal_dev_with_rng, gen_points_circle_normal_with_rng,
+        gen_points_circle_with_rng, gen_points_double_ring_3d, gen_points_fan_with_rng,
+        gen_points_fibonacci_disk, gen_points_on_circle, gen_points_poisson_disk_with_rng,
+        gen_points_polylines, gen_points_ring_3d, gen_points_sphere_3d, gen_points_svg_path,
+        gen_vectors_fibonacci_sphere, orientation, parse_svg_path,
+    },
+};
+use glam::{Quat, Vec2};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn points_on_circle_cover_the_whole_circle() {
+    let points = gen_points_on_circle(10., 500);
+    assert!(points.iter().all(|p| (p.length() - 10.).abs() < 1e-3));
+    assert!(points.iter().any(|p| p.y > 5.));
+    assert!(points.iter().any(|p| p.y < -5.));
+}
+
+#[test]
+fn tilted_ring_projects_to_an_ellipse() {
+    let tilt = PI / 3.;
+    let points = gen_points_ring_3d(10., 360, orientation(tilt, 0.));
+    let max_x = points.iter().map(|p| p.x.abs()).fold(0., f32::max);
+    let max_y = points.iter().map(|p| p.y.abs()).fold(0., f32::max);
+    assert!((max_x - 10.).abs() < 0.01);
+    assert!((max_y - 10. * tilt.cos()).abs() < 0.01);
+    // Every point lies on the ellipse
+    assert!(points
+        .iter()
+        .all(|p| ((p.x / 10.).powi(2) + (p.y / (10. * tilt.cos())).powi(2) - 1.).abs() < 1e-3));
+}
+
+#[test]
+fn ring_facing_the_viewer_is_a_circle() {
+    let points = gen_points_ring_3d(7., 100, Quat::IDENTITY);
+    assert_eq!(points.len(), 100);
+    assert!(points.iter().all(|p| (p.length() - 7.).abs() < 1e-3));
+    assert_eq!(
+        gen_points_double_ring_3d(7., 100, Quat::IDENTITY).len(),
+        200
+    );
+}
+
+#[test]
+fn sphere_points_stay_inside_the_radius() {
+    let points = gen_points_sphere_3d(5., 1000);
+    assert!(points.iter().all(|p| p.length() <= 5. + 1e-3));
+}
+
+#[test]
+fn same_seed_gives_same_points() {
+    let a = gen_points_circle_normal_with_rng(10., 200, &mut StdRng::seed_from_u64(7));
+    let b = gen_points_circle_normal_with_rng(10., 200, &mut StdRng::seed_from_u64(7));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn circle_and_fan_points_stay_inside_their_range() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let points = gen_points_circle_with_rng(6, 500, &mut rng);
+    assert_eq!(points.len(), 500);
+    assert!(points
+        .iter()
+        .all(|p| p.length() <= 6. && p.fract() == Vec2::ZERO));
+    assert!(points.iter().any(|p| p.length() > 4.));
+    assert_eq!(
+        gen_points_circle_with_rng(-2, 3, &mut rng),
+        vec![Vec2::ZERO; 3]
+    );
+
+    // Angles go counterclockwise from the right, and y points down
+    let fan = gen_points_fan_with_rng(10., 500, PI / 4., PI / 2., &mut rng);
+    assert_eq!(fan.len(), 500);
+    for p in fan {
+        assert!(p.length() <= 10. + 1e-3);
+        let a = (-p.y).atan2(p.x);
+        assert!((PI / 4. - 1e-3..=PI / 2. + 1e-3).contains(&a));
+    }
+    // Angles in reverse order or beyond a turn still give points
+    assert_eq!(gen_points_fan_with_rng(5., 20, PI, 0., &mut rng).len(), 20);
+    assert_eq!(
+        gen_points_fan_with_rng(5., 20, 3. * PI, 4. * PI, &mut rng).len(),
+        20
+    );
+}
+
+#[test]
+fn normal_points_handle_any_radius_and_deviation() {
+    let mut rng = StdRng::seed_from_u64(5);
+    for radius in [0., -3., f32::NAN] {
+        assert_eq!(
+            gen_points_circle_normal_dev_with_rng(radius, 4, 1., &mut rng),
+            vec![Vec2::ZERO; 4]
+        );
+    }
+    assert!(gen_points_circle_normal_dev_with_rng(5., 0, 1., &mut rng).is_empty());
+    for std_dev in [-1., 0., f32::NAN, f32::INFINITY, 1e30] {
+        let points = gen_points_circle_normal_dev_with_rng(5., 50, std_dev, &mut rng);
+        assert_eq!(points.len(), 50);
+        assert!(points.iter().all(|p| p.length() <= 5.));
+    }
+}
+
+#[test]
+fn fibonacci_patterns_stay_inside_the_radius() {
+    let points = gen_points_fibonacci_disk(10., 300);
+    assert_eq!(points.len(), 300);
+    assert!(points.iter().all(|p| p.length() <= 10. + 1e-3));
+    let vectors = gen_vectors_fibonacci_sphere(4., 100);
+    assert_eq!(vectors.len(), 100);
+    assert!(vectors.iter().all(|v| (v.length() - 4.).abs() < 1e-3));
+}
+
+#[test]
+fn poisson_disk_points_keep_their_distance() {
+    let points = gen_points_poisson_disk_with_rng(20., 3., &mut StdRng::seed_from_u64(1));
+    assert!(points.len() > 10);
+    assert!(points.iter().all(|p| p.length() <= 20. + 1e-3));
+    for (i, a) in points.iter().enumerate() {
+        for b in &points[i + 1..] {
+            assert!(a.distance(*b) >= 3. - 1e-3);
+        }
+    }
+    // A tiny distance is raised so that the sampling stays bounded
+    let points = gen_points_poisson_disk_with_rng(1., 1e-9, &mut StdRng::seed_from_u64(1));
+    assert!(points.len() < 50_000);
+}
+
+#[test]
+fn shell_built_with_same_seed_is_reproducible() {
+    for kind in [ShellKind::Peony, ShellKind::Palm, ShellKind::Willow] {
+        let shell = Shell::new(kind);
+        let build = |seed| {
+            shell.build_with_rng(
+                Vec2::ZERO,
+                Duration::ZERO,
+                false,
+                &mut StdRng::seed_from_u64(seed),
+            )
+        };
+        assert_eq!(build(3).particles, build(3).particles);
+    }
+}
+
+#[test]
+fn shell_names_round_trip() {
+    for kind in ShellKind::ALL {
+        assert_eq!(ShellKind::from_name(kind.name()), Some(kind));
+        assert_eq!(
+            ShellKind::from_name(&kind.name().to_uppercase()),
+            Some(kind)
+        );
+    }
+    assert_eq!(ShellKind::from_name("nope"), None);
+}
+
+#[test]
+fn shell_stars_scale_with_density() {
+    for kind in ShellKind::ALL {
+        let count = |density: f32| {
+            Shell::new(kind)
+                .with_density(density)
+                .build_with_rng(
+                    Vec2::ZERO,
+                    Duration::ZERO,
+                    false,
+                    &mut StdRng::seed_from_u64(5),
+                )
+                .particles
+                .len()
+        };
+        assert_eq!(count(2.), 2 * count(1.), "{:?}", kind);
+        // Every shell keeps at least one star
+        assert!(count(0.) >= 1);
+    }
+}
+
+#[test]
+fn svg_path_is_parsed_into_polylines() {
+    let polylines = parse_svg_path("M0,0 h10 v10 H0 z m20 0 l5-5 5,5 Q 35 10 40 0").unwrap();
+    assert_eq!(polylines.len(), 2);
+    assert_eq!(
+        polylines[0],
+        vec![
+            Vec2::new(0., 0.),
+            Vec2::new(10., 0.),
+            Vec2::new(10., 10.),
+            Vec2::new(0., 10.),
+            Vec2::new(0., 0.),
+        ]
+    );
+    assert_eq!(
+        polylines[1][..3],
+        [Vec2::new(20., 0.), Vec2::new(25., -5.), Vec2::new(30., 0.)]
+    );
+    assert_eq!(polylines[1].last(), Some(&Vec2::new(40., 0.)));
+    assert!(parse_svg_path("M 0 0 L 1").is_none());
+    assert!(parse_svg_path("10 10").is_none());
+}
+
+#[test]
+fn polyline_points_are_evenly_spaced_and_normalized() {
+    let square = vec![
+        Vec2::new(0., 0.),
+        Vec2::new(4., 0.),
+        Vec2::new(4., 4.),
+        Vec2::new(0., 4.),
+        Vec2::new(0., 0.),
+    ];
+    let points = gen_points_polylines(&[square], 16, 10.);
+    assert_eq!(points.len(), 16);
+    let max = points.iter().map(|p| p.length()).fold(0., f32::max);
+    assert!((max - 10.).abs() < 1e-3);
+    // Every point lies on the square outline, one step apart
+    let half = points[0].x.abs().max(points[0].y.abs());
+    assert!(points
+        .iter()
+        .all(|p| (p.x.abs().max(p.y.abs()) - half).abs() < 1e-3));
+    assert!((points[0].distance(points[1]) - half / 2.).abs() < 1e-3);
+    let curve = gen_points_svg_path("M0 0 C 0 10 10 10 10 0", 50, 5.).unwrap();
+    assert_eq!(curve.len(), 50);
+    assert!(curve.iter().all(|p| p.length() <= 5. + 1e-3));
+}
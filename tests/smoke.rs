@@ -0,0 +1,123 @@
+mod common;
+
+use std::time::Duration;
+
+use firework_rs::{
+    config::Config,
+    field::ForceField,
+    fireworks::{ExplosionForm, Firework, FireworkConfig, FireworkManager, FireworkState},
+    particle::ParticleConfig,
+    smoke::SmokeConfig,
+    term::Terminal,
+};
+use glam::Vec2;
+
+use common::{launch, Show};
+
+const CENTER: Vec2 = Vec2::new(20., 10.);
+
+/// A smoking firework at `CENTER` with a single particle living 0.5 s
+fn smoking(smoke: SmokeConfig, form: ExplosionForm) -> Firework {
+    Firework {
+        center: CENTER,
+        form,
+        config: FireworkConfig::default().with_smoke(smoke),
+        ..launch(vec![ParticleConfig::new(
+            CENTER,
+            Vec2::ZERO,
+            2,
+            Duration::from_millis(500),
+            (255, 255, 255),
+        )])
+    }
+}
+
+#[test]
+fn explosions_leave_a_cloud_around_the_center() {
+    let smoke = SmokeConfig::default().with_size(10, 8.);
+    let mut show = Show::of(smoking(smoke, ExplosionForm::Instant { used: false }));
+    let puffs = show.run(1).smoke();
+    assert_eq!(puffs.len(), 10);
+    assert!(puffs.iter().all(|p| p.config.color == smoke.color));
+    assert!(puffs.iter().all(|p| p.pos.distance(CENTER) <= 1.5));
+    assert!(puffs.iter().all(|p| p.vel.length() <= 8.));
+    // Only one cloud for an `Instant` firework
+    assert_eq!(show.run(10).smoke().len(), 10);
+
+    // A `Sustained` firework leaves a cloud every `interval` while it emits particles, at 0, 0.3, 0.6 and 0.9 s
+    let mut show = Show::of(smoking(
+        smoke.with_life_time(Duration::from_secs(10)),
+        ExplosionForm::Sustained {
+            lasts: Duration::from_millis(1000),
+            time_interval: Duration::from_millis(100),
+            timer: Duration::ZERO,
+        },
+    ));
+    assert_eq!(show.run(30).smoke().len(), 40);
+}
+
+#[test]
+fn smoke_rises_and_drifts_with_the_wind() {
+    let smoke = SmokeConfig::default()
+        .with_size(16, 0.)
+        .with_life_time(Duration::from_secs(10));
+    let cloud = || smoking(smoke, ExplosionForm::Instant { used: false });
+    // Puffs start within 1 of the center
+    let still = Show::of(cloud()).run(60).smoke();
+    assert_eq!(still.len(), 16);
+    assert!(still.iter().all(|p| p.pos.y < CENTER.y + 1.));
+    let mean_y = still.iter().map(|p| p.pos.y).sum::<f32>() / still.len() as f32;
+    assert!(mean_y < CENTER.y - 0.5);
+    let windy = Show::new(
+        FireworkManager::default()
+            .with_force_field(ForceField::wind(Vec2::new(5., 0.)))
+            .with_firework(cloud()),
+    )
+    .run(60)
+    .smoke();
+    assert!(windy.iter().all(|p| p.pos.x > CENTER.x + 2.));
+}
+
+#[test]
+fn fireworks_are_gone_once_their_smoke_has_cleared() {
+    let smoke = SmokeConfig::default().with_life_time(Duration::from_secs(2));
+    let mut show = Show::of(smoking(smoke, ExplosionForm::Instant { used: false }));
+    // The particle is dead after 0.5 s, the smoke lives up to 2 s
+    show.run(20);
+    assert!(show.particles().is_empty());
+    assert!(!show.smoke().is_empty());
+    assert_eq!(show.fm.fireworks[0].state, FireworkState::Alive);
+    assert!(!show.fm.is_finished());
+    show.run(22);
+    assert!(show.smoke().is_empty());
+    assert!(show.fm.fireworks[0].is_gone());
+    assert!(show.fm.is_finished());
+}
+
+#[test]
+fn puffs_are_drawn_square_and_cut_at_the_edges() {
+    let smoke = SmokeConfig::default()
+        .with_size(1, 0.)
+        .with_life_time(Duration::from_secs(10));
+    // Columns of the puff on a screen `width` wide
+    let drawn = |cfg: &Config, width: u16| {
+        let mut show = Show::of(smoking(smoke, ExplosionForm::Instant { used: false }));
+        // The particle is dead, only the puff is left
+        show.run(12);
+        let mut term = Terminal::from_size((width, 20));
+        term.render(&show.fm, cfg);
+        term.screen
+            .iter()
+            .flat_map(|row| row.iter().enumerate())
+            .filter(|(_, c)| "▒░".contains(c.text))
+            .map(|(x, _)| x)
+            .collect::<Vec<_>>()
+    };
+    // Two ascii cells or one cjk cell make a square
+    let ascii = drawn(&Config::default(), 60);
+    assert_eq!(ascii.len(), 2);
+    assert_eq!(ascii[1], ascii[0] + 1);
+    assert_eq!(drawn(&Config::new(true), 40).len(), 1);
+    // Only the left half fits in a screen ending in the middle of the puff
+    assert_eq!(drawn(&Config::default(), ascii[1] as u16), [ascii[0]]);
+}